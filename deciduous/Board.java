@@ -24,33 +24,11 @@ import static deciduous.Color.*;
  *  The board representation itself is a bitboard 
  *  @author Andrew Berger */
 class Board {
-    
+
+    /** The tables are built statically, so there is nothing left to do here.
+     *  Everything in this class is static; call it directly. */
+    @Deprecated
     Board() {
-        clearRank = new long[8];
-        clearFile = new long[8];
-        maskRank = new long[8];
-        maskFile = new long[8];
-        for (int i = 0; i < 8; i++) {
-            maskRank[i] = fillRank(i);
-            maskFile[i] = fillFile(i);
-            clearRank[i] = ~maskRank[i];
-            clearFile[i] = ~maskFile[i];
-        }
-        north = new long[64];
-        northEa = new long[64];
-        west = new long[64];
-        southWe = new long[64];
-        south = new long[64];
-        southEa = new long[64];
-        east = new long[64];
-        northWe = new long[64];
-        initRays();
-        knightAttacks = new long[64];
-        initKnightAttacks();
-        Random rgen = new Random();
-        for (int k = 0; k < 9; k++) {
-            hMultipliers[k] = rgen.nextLong();
-        }
     }
     
     
@@ -86,29 +64,95 @@ class Board {
         return board;
     }
 
+    /** Builds a board from an explicit layout.
+     *  PIECES and COLORS are 64 wide, indexed like the bitboards; PIECES[s]
+     *  is 2-7 for an occupied square and 0 for an empty one, in which case
+     *  COLORS[s] is ignored. Throws IllegalArgumentException if either
+     *  array isn't 64 wide, a piece is out of range or has no color, or
+     *  a side doesn't have exactly one king. */
+    public static long[] fromSquares(int[] pieces, Color[] colors) {
+        if (pieces.length != 64 || colors.length != 64) {
+            throw new IllegalArgumentException("Layout must be 64 squares wide");
+        }
+        long[] board = new long[9];
+        for (int s = 0; s < 64; s++) {
+            if (pieces[s] == 0) {
+                continue;
+            }
+            if (pieces[s] < 2 || pieces[s] > 7) {
+                throw new IllegalArgumentException("Bad piece " + pieces[s] + " on " + Move.squareName(s));
+            }
+            if (colors[s] == null) {
+                throw new IllegalArgumentException("No color for the piece on " + Move.squareName(s));
+            }
+            setPiece(board, s, pieces[s], colors[s]);
+        }
+        if (Long.bitCount(board[0] & board[6]) != 1 || Long.bitCount(board[1] & board[6]) != 1) {
+            throw new IllegalArgumentException("Each side needs exactly one king");
+        }
+        return board;
+    }
+
+    /** Checks that BOARD, with PLAYER to move, is consistent and legal
+     *  enough to play from: the color and piece bitboards agree, each side
+     *  has exactly one king, no pawn stands on the first or last rank, and
+     *  the side that just moved didn't leave its king in check.
+     *  Throws IllegalArgumentException describing the first problem found. */
+    public static void validate(long[] board, Color player) {
+        long occupied = occupancy(board);
+        long pieces = 0;
+        for (int p = 2; p <= 7; p++) {
+            if ((pieces & board[p]) != 0) {
+                throw new IllegalArgumentException("A square holds two pieces");
+            }
+            pieces |= board[p];
+        }
+        if ((board[0] & board[1]) != 0 || pieces != occupied) {
+            throw new IllegalArgumentException("Color and piece bitboards disagree");
+        }
+        if (Long.bitCount(board[0] & board[6]) != 1 || Long.bitCount(board[1] & board[6]) != 1) {
+            throw new IllegalArgumentException("Each side needs exactly one king");
+        }
+        if ((board[2] & (maskRank[0] | maskRank[7])) != 0) {
+            throw new IllegalArgumentException("Pawn on the first or last rank");
+        }
+        Color other = player.opposite();
+        int king = bitscanLSD(board[other.index()] & board[6]);
+        if (isAttacked(board, king, player)) {
+            throw new IllegalArgumentException("Side not to move is in check");
+        }
+    }
+
     /** Core operation. 
      *  MOVE is of format {currSquare, finalSquare}
      *  Returns the modified board
      *  Doesn't yet handle promotions or en passant, of course*/
     public static long[] make(long[] board, Move m) {
         if (m.isWhite()) {
-            board[0] ^= (1 << m.getCoords()[0]);
-            board[0] ^= (1 << m.getCoords()[1]);
+            board[0] ^= (1L << m.getCoords()[0]);
+            board[0] ^= (1L << m.getCoords()[1]);
             if (m.isCapture()) {
-                board[1] ^= (1 << m.getCoords()[1]);
+                board[1] ^= (1L << m.getCoords()[1]);
             }             
         } else {
-            board[1] ^= (1 << m.getCoords()[0]);
-            board[1] ^= (1 << m.getCoords()[1]);
+            board[1] ^= (1L << m.getCoords()[0]);
+            board[1] ^= (1L << m.getCoords()[1]);
             if (m.isCapture()) {
-                board[0] ^= (1 << m.getCoords()[1]);
+                board[0] ^= (1L << m.getCoords()[1]);
             }             
         }
         if (m.isCapture()) {
-            board[m.getCapture()] ^= (1 << m.getCoords()[1]);
+            board[m.getCapture()] ^= (1L << m.getCoords()[1]);
         }
-        board[m.getPiece()] ^= (1 << m.getCoords()[0]);
-        board[m.getPiece()] ^= (1 << m.getCoords()[1]);
+        board[m.getPiece()] ^= (1L << m.getCoords()[0]);
+        board[m.getPiece()] ^= (1L << m.getCoords()[1]);
+        return board;
+    }
+
+    /** Like make, but leaves BOARD untouched and returns the child position
+     *  in a fresh array. Handy when the parent needs to stick around. */
+    public static long[] withMove(long[] board, Move m) {
+        return make(board.clone(), m);
     }
 
 
@@ -121,6 +165,249 @@ class Board {
     }
 
 
+    /** Puts PIECE, an integer 2-7, of PLAYER on SQUARE of BOARD,
+     *  replacing whatever stood there. No check is made that there is
+     *  still one king per side; that is up to the caller. */
+    public static void setPiece(long[] board, int square, int piece, Color player) {
+        checkSquare(square);
+        removePiece(board, square);
+        board[player.index()] |= 1L << square;
+        board[piece] |= 1L << square;
+    }
+
+    /** Empties SQUARE of BOARD on every bitboard. */
+    public static void removePiece(long[] board, int square) {
+        checkSquare(square);
+        long clear = ~(1L << square);
+        for (int r = 0; r < 8; r++) {
+            board[r] &= clear;
+        }
+    }
+
+    /** Returns the piece, an integer 2-7, standing on SQUARE of BOARD,
+     *  or 0 if the square is empty. SQUARE must be 0-63. */
+    public static int pieceAt(long[] board, int square) {
+        checkSquare(square);
+        long bit = 1L << square;
+        if ((occupancy(board) & bit) == 0) {
+            return 0;
+        }
+        for (int p = 2; p <= 7; p++) {
+            if ((board[p] & bit) != 0) {
+                return p;
+            }
+        }
+        return 0;
+    }
+
+    /** Returns the color of the piece on SQUARE of BOARD, 0-63, or null if it is empty. */
+    public static Color colorAt(long[] board, int square) {
+        checkSquare(square);
+        long bit = 1L << square;
+        if ((board[0] & bit) != 0) {
+            return WHITE;
+        } else if ((board[1] & bit) != 0) {
+            return BLACK;
+        }
+        return null;
+    }
+
+    /** Returns the state of squares whose contents differ between A and B,
+     *  in piece or in color. Walk it with bitscanLSD and look each square up
+     *  with pieceAt/colorAt on both boards to see what changed. */
+    public static long diff(long[] a, long[] b) {
+        long changed = 0;
+        for (int r = 0; r < 8; r++) {
+            changed |= a[r] ^ b[r];
+        }
+        return changed;
+    }
+
+    /** Checked version of make for moves from outside the search, e.g. user input.
+     *  Plays M on BOARD and returns it, or throws IllegalArgumentException
+     *  saying why M is illegal, in which case BOARD is left untouched. */
+    public static long[] tryMake(long[] board, Move m) {
+        String problem = illegality(board, m);
+        if (problem != null) {
+            throw new IllegalArgumentException(problem);
+        }
+        return make(board, m);
+    }
+
+    /** True iff M is legal on BOARD: the right piece of the right color
+     *  stands on the source square, the destination agrees with whether M
+     *  is a capture, the piece can actually get there, and the mover's king
+     *  isn't left in check. Safe to call with any Move at all. */
+    public static boolean isLegal(long[] board, Move m) {
+        return illegality(board, m) == null;
+    }
+
+    /** Returns why M is illegal on BOARD, or null if it is legal. See isLegal */
+    private static String illegality(long[] board, Move m) {
+        Color player = m.isWhite() ? WHITE : BLACK;
+        int from = m.getCoords()[0];
+        int to = m.getCoords()[1];
+        if (from < 0 || from > 63 || to < 0 || to > 63) {
+            return "Square off the board";
+        }
+        if (pieceAt(board, from) != m.getPiece() || colorAt(board, from) != player) {
+            return "No such piece on " + Move.squareName(from);
+        }
+        if (m.isCapture()) {
+            if (m.getCapture() == 6) {
+                return "Kings can't be captured";
+            }
+            if (colorAt(board, to) != player.opposite() || pieceAt(board, to) != m.getCapture()) {
+                return "Nothing to capture on " + Move.squareName(to);
+            }
+        } else if (colorAt(board, to) != null) {
+            return Move.squareName(to) + " is occupied";
+        }
+        if ((reach(board, from, m.getPiece(), player) & (1L << to)) == 0) {
+            return "Piece can't reach " + Move.squareName(to);
+        }
+        long[] after = withMove(board, m);
+        int king = bitscanLSD(after[player.index()] & after[6]);
+        if (isAttacked(after, king, player.opposite())) {
+            return "Leaves the king in check";
+        }
+        return null;
+    }
+
+    /** Returns every square PIECE of PLAYER on SQUARE could move to,
+     *  captures included, ignoring whether its own king ends up in check. */
+    private static long reach(long[] board, int square, int piece, Color player) {
+        switch (piece) {
+        case 2:
+            long pushes;
+            if (player == WHITE) {
+                pushes = (wPawnPushes(board) & (1L << (square + 8)))
+                    | (wPawnDoublePushes(board) & (1L << (square + 16)));
+            } else {
+                pushes = (bPawnPushes(board) & (1L << (square - 8)))
+                    | (bPawnDoublePushes(board) & (1L << (square - 16)));
+            }
+            return pushes | (pawnAttacksFrom(square, player) & board[player.opposite().index()]);
+        case 3:
+            return bishopMoves(board, square, player);
+        case 4:
+            return knightMoves(board, square, player);
+        case 5:
+            return rookMoves(board, square, player);
+        case 6:
+            return kingAttacks[square] & ~board[player.index()];
+        case 7:
+            return queenMoves(board, square, player);
+        default:
+            return 0;
+        }
+    }
+
+    /** One occupied square of a board, see pieces */
+    static class Placement {
+        Placement(int square, int piece, Color color) {
+            _square = square;
+            _piece = piece;
+            _color = color;
+        }
+
+        /** The square, 0-63 from a1 whatever the side to move */
+        int square() {
+            return _square;
+        }
+
+        /** The piece, an integer 2-7 */
+        int piece() {
+            return _piece;
+        }
+
+        Color color() {
+            return _color;
+        }
+
+        private final int _square;
+        private final int _piece;
+        private final Color _color;
+    }
+
+    /** Returns every occupied square of BOARD with what stands on it, from
+     *  a1 upwards. Squares are absolute, as in pieceAt. A convenience for
+     *  rendering and the like; it allocates for every piece, so loops that
+     *  care about speed should bitscan the bitboards themselves. */
+    public static List<Placement> pieces(long[] board) {
+        List<Placement> result = new ArrayList<Placement>();
+        long occupied = occupancy(board);
+        while (occupied != 0) {
+            int square = bitscanLSD(occupied);
+            result.add(new Placement(square, pieceAt(board, square), colorAt(board, square)));
+            occupied &= occupied - 1;
+        }
+        return result;
+    }
+
+    /** Returns how many of each piece both sides have on BOARD.
+     *  The first index is the color's index, the second the piece, 2-7,
+     *  so material(board)[BLACK.index()][5] is the number of black rooks. */
+    public static int[][] material(long[] board) {
+        int[][] counts = new int[2][8];
+        for (int c = 0; c < 2; c++) {
+            for (int p = 2; p <= 7; p++) {
+                counts[c][p] = Long.bitCount(board[c] & board[p]);
+            }
+        }
+        return counts;
+    }
+
+    /** Splits BOARD into twelve planes, one per piece and color, for
+     *  feeding a network: white pawns, bishops, knights, rooks, kings and
+     *  queens in bitboard order, then the same six for black. */
+    public static long[] toPlanes(long[] board) {
+        long[] planes = new long[12];
+        for (int c = 0; c < 2; c++) {
+            for (int p = 2; p <= 7; p++) {
+                planes[c * 6 + p - 2] = board[c] & board[p];
+            }
+        }
+        return planes;
+    }
+
+    /** Returns a picture of BOARD, eighth rank on top, white pieces in
+     *  upper case and black in lower case. Squares set in OVERLAY get a
+     *  '*' in front of them, e.g. to show an attack set or a move's targets. */
+    public static String render(long[] board, long overlay) {
+        return render(board, overlay, false, false);
+    }
+
+    /** Like render(BOARD, OVERLAY), but with black at the bottom if FLIP,
+     *  and with rank numbers and file letters along the edges if LABELS. */
+    public static String render(long[] board, long overlay, boolean flip, boolean labels) {
+        StringBuilder out = new StringBuilder();
+        for (int row = 0; row < 8; row++) {
+            int rank = flip ? row : 7 - row;
+            if (labels) {
+                out.append((char) ('1' + rank));
+            }
+            for (int col = 0; col < 8; col++) {
+                int square = rank * 8 + (flip ? 7 - col : col);
+                char symbol = PIECE_SYMBOLS.charAt(pieceAt(board, square));
+                if (colorAt(board, square) == BLACK) {
+                    symbol = Character.toLowerCase(symbol);
+                }
+                out.append((overlay & (1L << square)) != 0 ? '*' : ' ');
+                out.append(symbol);
+            }
+            out.append('\n');
+        }
+        if (labels) {
+            out.append(' ');
+            for (int col = 0; col < 8; col++) {
+                out.append(' ').append((char) ('a' + (flip ? 7 - col : col)));
+            }
+            out.append('\n');
+        }
+        return out.toString();
+    }
+
     /** First attempt at designing hash function. 
      *  This one will take the rather naive approach of multiplying
      *  each board state by a random long and xoring them together.
@@ -137,6 +424,14 @@ class Board {
     /** Returns list of pseudo legal moves 
      *  Doesn't distinguish between captures and quiet moves*/
     public static List<Move> generateMoves(long[] board, Color player) {
+        List<Move> moves = generateQuietMoves(board, player);
+        moves.addAll(generateCaptures(board, player));
+        return moves;
+    }
+
+    /** Returns list of pseudo legal moves that capture nothing.
+     *  Together with generateCaptures this covers every pseudo legal move once. */
+    public static List<Move> generateQuietMoves(long[] board, Color player) {
         List<Move> moves = new ArrayList<Move>();
         moves.addAll(generateBishopMoves(board, player));
         moves.addAll(generateRookMoves(board, player));
@@ -158,6 +453,87 @@ class Board {
         return moves;
     }
 
+    /** Returns the legal moves of PLAYER that give check.
+     *  Only moves landing on a square that hits the enemy king, or leaving
+     *  a square on one of its lines (a possible discovered check), are
+     *  worth handing to givesCheck, so the rest are skipped up front. */
+    public static List<Move> generateChecks(long[] board, Color player) {
+        List<Move> checks = new ArrayList<Move>();
+        long king = board[player.opposite().index()] & board[6];
+        if (king == 0) {
+            return checks;
+        }
+        int k = bitscanLSD(king);
+        long diag = slideAttacks(board, k, DIAGONALS);
+        long rect = slideAttacks(board, k, ORTHOGONALS);
+        long[] checkSquares = new long[8];
+        checkSquares[2] = pawnSources(k, player);
+        checkSquares[3] = diag;
+        checkSquares[4] = knightAttacks[k];
+        checkSquares[5] = rect;
+        checkSquares[7] = diag | rect;
+        long discoverers = (diag | rect) & board[player.index()];
+        List<Move> candidates = generateMoves(board, player);
+        for (Move m : candidates) {
+            long from = 1L << m.getCoords()[0];
+            long to = 1L << m.getCoords()[1];
+            if ((from & discoverers) == 0 && (to & checkSquares[m.getPiece()]) == 0) {
+                continue;
+            }
+            if (givesCheck(board, m) && isLegal(board, m)) {
+                checks.add(m);
+            }
+        }
+        return checks;
+    }
+
+    /** True iff PLAYER is not in check but has no legal move.
+     *  A king with a safe square to step to settles it without
+     *  generating anything else, which is by far the common case. */
+    public static boolean isStalemate(long[] board, Color player) {
+        long king = board[player.index()] & board[6];
+        if (king == 0) {
+            return false;
+        }
+        int square = bitscanLSD(king);
+        if (isAttacked(board, square, player.opposite())) {
+            return false;
+        }
+        long[] kingless = board.clone();
+        kingless[player.index()] ^= king;
+        kingless[6] ^= king;
+        long steps = kingMoves(board, player);
+        while (steps != 0) {
+            if (!isAttacked(kingless, bitscanLSD(steps), player.opposite())) {
+                return false;
+            }
+            steps &= steps - 1;
+        }
+        List<Move> moves = generateMoves(board, player);
+        for (Move m : moves) {
+            if (isLegal(board, m)) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    /** Returns how many legal moves PLAYER has on BOARD, the pseudo legal
+     *  ones that don't leave its king in check. Each is made and unmade on
+     *  BOARD itself rather than checked with isLegal, so BOARD is played on
+     *  but left as it was found. */
+    public static int countLegalMoves(long[] board, Color player) {
+        int count = 0;
+        for (Move m : generateMoves(board, player)) {
+            make(board, m);
+            if (!inCheck(board, player)) {
+                count += 1;
+            }
+            unmake(board, m);
+        }
+        return count;
+    }
+
     /** Returns an ArrayList of all non captures moves rooks on BOARD of COLOR can make. */
     public static ArrayList<Move> generateRookMoves(long[] board, Color player) {
         ArrayList<Move> rookMoves = new ArrayList<Move>();
@@ -166,7 +542,7 @@ class Board {
             int rookPos = bitscanLSD(rooks);
             long rookPseudos = rookMoves(board, rookPos, player) & ~board[player.opposite().index()];
             rookMoves.addAll(parseMoves(rookPos, rookPseudos, player, 5));
-            rooks ^= (1 << rookPos);
+            rooks ^= (1L << rookPos);
         }
         return rookMoves;
     }
@@ -179,7 +555,7 @@ class Board {
             int bishopPos = bitscanLSD(bishops);
             long bishopPseudos = bishopMoves(board, bishopPos, player) & ~board[player.opposite().index()];
             bishopMoves.addAll(parseMoves(bishopPos, bishopPseudos, player, 3));
-            bishops  ^= (1 << bishopPos);
+            bishops  ^= (1L << bishopPos);
         }
         return bishopMoves;
     }
@@ -189,9 +565,9 @@ class Board {
         long knights = board[player.index()] & board[4];
         while (knights != 0) {
             int knightPos = bitscanLSD(knights);
-            long knightPseudos =  knightAttacks[knightPos] & ~board[player.index()] & ~board[player.opposite().index()];
+            long knightPseudos =  knightAttacks[knightPos] & ~occupancy(board);
             knightMoves.addAll(parseMoves(knightPos, knightPseudos, player, 4));
-            knights ^= (1 << knightPos);
+            knights ^= (1L << knightPos);
         }
         return knightMoves;
     }
@@ -204,6 +580,7 @@ class Board {
             int queenPos = bitscanLSD(queens);
             long queenPseudos = queenMoves(board, queenPos, player) & ~board[player.opposite().index()];
             queenMoves.addAll(parseMoves(queenPos, queenPseudos, player, 7));
+            queens ^= (1L << queenPos);
         }
         return queenMoves;
     }
@@ -216,66 +593,36 @@ class Board {
         return parseMoves(kingPos, kingPseudos, player, 6);
     } 
 
+    /** Returns an ArrayList of all the pushes, single and double, the pawns
+     *  on BOARD of PLAYER can make. Pawn captures are in generatePawnCaptures. */
     public static ArrayList<Move> generatePawnMoves(long[] board, Color player) {
-        ArrayList<int[]> pawnMoves = new ArrayList<int[]>();
-        int file = 0;
-        int pushIncr, lcIncr, rcIncr;
-        long pushes, dPushes, lCapt, rCapt;
+        ArrayList<Move> pawnMoves = new ArrayList<Move>();
+        int pushIncr;
+        long pushes, dPushes;
         if (player == WHITE) {
-            pushIncr = -8;
-            lcIncr = -7;
-            rcIncr = -9;
+            pushIncr = 8;
             pushes = wPawnPushes(board);
             dPushes = wPawnDoublePushes(board);
-            lCapt = wPawnLeftCaptures(board);
-            rCapt = wPawnRightCaptures(board);
         } else {
-            pushIncr = 8;
-            //this might be wrong!! 
-            lcIncr = 9;
-            rcIncr = 7;
+            pushIncr = -8;
             pushes = bPawnPushes(board);
             dPushes = bPawnDoublePushes(board);
-            lCapt = bPawnLeftCaptures(board);
-            rCapt = bPawnRightCaptures(board);
         }
         while (pushes != 0) {
-            long pushFile = pushes & maskFile[file];
-            long dPushFile = dPushes & maskFile[file];
-            if (pushFile != 0) {
-                int[] m = new int[2];
-                m[1] = bitscanLSD(pushFile);
-                m[0] = m[1] + pushIncr;
-                pawnMoves.add(m);
-                pushes ^= (1 << m[1]);
-            }
-            if (dPushFile != 0) {
-                int[] d = new int[2];
-                //computing extraneous information all is needed is an array of booleans 
-                int[] m = pawnMoves.get(pawnMoves.size());
-                d[0] = m[0];
-                d[1] = m[1] - pushIncr;
-                pawnMoves.add(d);
-            }
-            file += 1;
-        }
-        while (lCapt != 0) {
-            int[] l = new int[2];
-            l[1] = bitscanLSD(lCapt);
-            l[0] = l[1] + lcIncr;
-            lCapt ^= (1 << l[1]);
-        }
-        while (rCapt != 0) {
-            int[] r = new int[2];
-            r[1] = bitscanLSD(rCapt);
-            r[0] = r[1] + rcIncr;
-            rCapt ^= (1 << r[1]);
+            int[] m = new int[2];
+            m[1] = bitscanLSD(pushes);
+            m[0] = m[1] - pushIncr;
+            pawnMoves.add(new Move(m, 2, player));
+            pushes ^= (1L << m[1]);
         }
-        ArrayList<Move> pMoves = new ArrayList<Move>();
-        for (int[] m : pawnMoves) {
-            pMoves.add(new Move(m, 2, player));
+        while (dPushes != 0) {
+            int[] d = new int[2];
+            d[1] = bitscanLSD(dPushes);
+            d[0] = d[1] - 2 * pushIncr;
+            pawnMoves.add(new Move(d, 2, player));
+            dPushes ^= (1L << d[1]);
         }
-        return pMoves;
+        return pawnMoves;
     }
 
     public static ArrayList<Move> generateRookCaptures(long[] board, Color player) {
@@ -285,7 +632,7 @@ class Board {
             int rookPos = bitscanLSD(rooks);
             long rookPseudos = rookMoves(board, rookPos, player) & board[player.opposite().index()];
             rookCaptures.addAll(parseCaptures(rookPos, rookPseudos, player, 5, board));
-            rooks ^= (1 << rookPos);
+            rooks ^= (1L << rookPos);
         }
         return rookCaptures;
     }
@@ -296,8 +643,8 @@ class Board {
         while (bishops != 0) {
             int bishopPos = bitscanLSD(bishops);
             long bishopPseudos = bishopMoves(board, bishopPos, player) & board[player.opposite().index()];
-            bishopCaptures.addAll(parseCaptures(bishopPos, bishopPseudos, player, 3, captures));
-            bishops ^= (1 << bishopPos);
+            bishopCaptures.addAll(parseCaptures(bishopPos, bishopPseudos, player, 3, board));
+            bishops ^= (1L << bishopPos);
         }
         return bishopCaptures;
     }
@@ -309,7 +656,7 @@ class Board {
             int knightPos = bitscanLSD(knights);
             long knightPseudos = knightAttacks[knightPos] & board[player.opposite().index()];
             knightCaptures.addAll(parseCaptures(knightPos, knightPseudos, player, 4, board));
-            knights ^= (1 << knightPos);
+            knights ^= (1L << knightPos);
         }
         return knightCaptures;
     }
@@ -318,23 +665,31 @@ class Board {
         ArrayList<Move> queenMoves = new ArrayList<Move>();
         long queens = board[player.index()] & board[7];
         while (queens != 0) {
-            long queenPos = bitscanLSD(queen);
+            int queenPos = bitscanLSD(queens);
             long queenPseudos = queenMoves(board, queenPos, player) & board[player.opposite().index()];
             queenMoves.addAll(parseCaptures(queenPos, queenPseudos, player, 7, board));
+            queens ^= (1L << queenPos);
         }
-                
+        return queenMoves;
     }
 
     public static ArrayList<Move> generateKingCaptures(long[] board, Color player) {
         long king = board[player.index()] & board[6];
-        long kingPos = bitscanLSD(king);
+        int kingPos = bitscanLSD(king);
         long kingPseudos = kingMoves(board, player) & board[player.opposite().index()];
         return parseCaptures(kingPos, kingPseudos, player, 6, board);
     }
 
     public static ArrayList<Move> generatePawnCaptures(long[] board, Color player) {
-        //this one is more complicated 
-        return null;
+        ArrayList<Move> pawnCaptures = new ArrayList<Move>();
+        long pawns = board[player.index()] & board[2];
+        while (pawns != 0) {
+            int pawnPos = bitscanLSD(pawns);
+            long pawnPseudos = pawnAttacks[player.index()][pawnPos] & board[player.opposite().index()];
+            pawnCaptures.addAll(parseCaptures(pawnPos, pawnPseudos, player, 2, board));
+            pawns ^= (1L << pawnPos);
+        }
+        return pawnCaptures;
     }
     
 
@@ -349,64 +704,127 @@ class Board {
             m[1] = bitscanLSD(moves);
             Move myMove = new Move(m, piece, player);
             movePairs.add(myMove);
-            moves ^= (1 << m[1]);
+            moves ^= (1L << m[1]);
         } 
         return movePairs;
     }
 
     /** Commonly used operation in move gen.
      *  Returns the ArrayList of capture (move objects)
-     *  PIECE is an integer 2-7 representing the type of the piece that moves. */
+     *  PIECE is an integer 2-7 representing the type of the piece that moves.
+     *  Never emits a king capture. */
     public static ArrayList<Move> parseCaptures(int piecePos, long moves, Color player, int piece, long[] board) {
         ArrayList<Move> captures = new ArrayList<Move>();
+        //a king that can be taken means the last move was illegal, see inCheck
+        assert (moves & board[6]) == 0 : "King capture generated, the last move left its king in check";
+        moves &= ~board[6];
         while (moves != 0) {
-            int m = new int[2];
+            int[] m = new int[2];
             m[0] = piecePos;
-            m[1] = bitScanForward(moves);
+            m[1] = bitscanLSD(moves);
             Move myMove =  new Move(m, piece, player);
             for (int p = 2; p <= 7; p++) {
-                if ((board[p]  & (1 << m[1])) != 0) {
+                if ((board[p]  & (1L << m[1])) != 0) {
                     myMove.setCapture(p);
                     break;
                 } 
             }
             captures.add(myMove);
-            moves ^= (1 << m[1]);
+            moves ^= (1L << m[1]);
         }
         return captures;
     }
 
+    /** Returns the state of every occupied square, of either color.
+     *  The two color bitboards are disjoint, so this is their union. */
+    public static long occupancy(long[] board) {
+        return board[0] | board[1];
+    }
+
+    /** Number of king steps between squares A and B */
+    public static int chebyshevDistance(int a, int b) {
+        return Math.max(Math.abs(a % 8 - b % 8), Math.abs(a / 8 - b / 8));
+    }
+
+    /** Number of rook steps of one square between squares A and B */
+    public static int manhattanDistance(int a, int b) {
+        return Math.abs(a % 8 - b % 8) + Math.abs(a / 8 - b / 8);
+    }
+
     /** Very important routine
      *  Naive implementation with java library call
      *  Deserving of some de bruijn magic*/
     public static int bitscanLSD(long state) {
         long lsb = state & -state;
-        return Long.numberOfTrailingZeroes(lsb);
+        return Long.numberOfTrailingZeros(lsb);
     }
 
+    /** Index of the highest set bit of STATE, or -1 if STATE is empty */
     public static int bitscanMSD(long state) {
-        //not sure how to implement this simply
+        return 63 - Long.numberOfLeadingZeros(state);
     }
 
     
     /** Returns the state representing the squares sliding piece on SQUARE can move
      *  Very naive implementation and there are definitely fancier ways of doing sliding move generation 
-     *  I think there is a bug when the board is empty
+     *  The nearest obstacle is the lowest bit on positive rays and the highest on negative ones
      *  DIR only has certain allowed values refer to ray doc*/
     private static long rayAttack(long[] board, int square, int dir) {
+        return rayAttack(occupancy(board), square, dir);
+    }
+
+    /** Like rayAttack, but stopped by the squares of OCCUPANCY */
+    private static long rayAttack(long occupancy, int square, int dir) {
         long r = ray(square, dir);
-        long occupancy = board[0] & board[1];
         long obstacles = r & occupancy;
-        //turn on MSB?
-        long unavailable = ray(bitscanLSD(obstacles), dir);
+        if (obstacles == 0) {
+            return r;
+        }
+        int nearest = dir > 0 ? bitscanLSD(obstacles) : bitscanMSD(obstacles);
+        long unavailable = ray(nearest, dir);
         return  r ^ unavailable;
     }
 
+    /** Returns the union of rayAttack from SQUARE along each of ORIENTATIONS */
+    private static long slideAttacks(long[] board, int square, int[] orientations) {
+        return slideAttacks(occupancy(board), square, orientations);
+    }
+
+    /** Like slideAttacks, but stopped by the squares of OCCUPANCY */
+    private static long slideAttacks(long occupancy, int square, int[] orientations) {
+        long attacks = 0;
+        for (int dir : orientations) {
+            attacks |= rayAttack(occupancy, square, dir);
+        }
+        return attacks;
+    }
+
+    /** Returns the squares a slider on SQUARE only sees once those of BLOCKERS
+     *  its rays run into are lifted off BOARD, i.e. what it attacks through them. */
+    private static long xrayAttacks(long[] board, int square, long blockers, int[] orientations) {
+        long attacks = slideAttacks(board, square, orientations);
+        blockers &= attacks;
+        long[] lifted = board.clone();
+        lifted[0] &= ~blockers;
+        lifted[1] &= ~blockers;
+        return attacks ^ slideAttacks(lifted, square, orientations);
+    }
+
+    /** X-ray attacks of a rook on SQUARE through BLOCKERS. See xrayAttacks */
+    public static long xrayRookAttacks(long[] board, int square, long blockers) {
+        return xrayAttacks(board, square, blockers, ORTHOGONALS);
+    }
+
+    /** X-ray attacks of a bishop on SQUARE through BLOCKERS. See xrayAttacks */
+    public static long xrayBishopAttacks(long[] board, int square, long blockers) {
+        return xrayAttacks(board, square, blockers, DIAGONALS);
+    }
+
     /** Returns the board state of possible single pawn pushes 
      *  Does not treat captures or promotion*/
     public static long wPawnPushes(long[] board) {
         long pawns = board[0] & board[2];
-        long occ = board[0] & board[1];
+        long occ = occupancy(board);
         return (pawns << 8) & ~occ;
     }
 
@@ -414,22 +832,26 @@ class Board {
      *  Does not treat captures or promotion*/
     public static long bPawnPushes(long[] board) {
         long pawns = board[1] & board[2];
-        long occ = board[0] & board[1];
+        long occ = occupancy(board);
         return (pawns >>> 8) & ~occ;
     }
 
+    /** Returns the board state of possible double pawn pushes.
+     *  Only single pushes that land on the third rank may push again,
+     *  so the pawn must start at home and can't jump over a blocker. */
     public static long wPawnDoublePushes(long[] board) {
-        long pawns = board[0] & board[2];
-        long occ = board[0] & board[1];
-        long canJump = pawns & maskRank[1];
-        return (canJump << 16) & ~occ;
+        long occ = occupancy(board);
+        long canJump = wPawnPushes(board) & maskRank[2];
+        return (canJump << 8) & ~occ;
     }
 
+    /** Returns the board state of possible double pawn pushes.
+     *  Only single pushes that land on the sixth rank may push again,
+     *  so the pawn must start at home and can't jump over a blocker. */
     public static long bPawnDoublePushes(long[] board) {
-        long pawns = board[1] & board[2];
-        long occ = board[0] & board[1];
-        long canJump = pawns & maskRank[6];
-        return (canJump >>> 16) & ~occ;
+        long occ = occupancy(board);
+        long canJump = bPawnPushes(board) & maskRank[5];
+        return (canJump >>> 8) & ~occ;
     }
 
 
@@ -437,7 +859,7 @@ class Board {
      *  Does not calculate en pasant */    
     public static long wPawnRightCaptures(long[] board) {
         long pawns = board[0] & board[2];
-        pawns &= clearFile[0];
+        pawns &= clearFile[7];
         long rightMoves = pawns << 9;
         return rightMoves & board[1];
     }
@@ -446,7 +868,7 @@ class Board {
      *  Does not calculate en pasant */
     public static long bPawnRightCaptures(long[] board) {
         long pawns = board[1] & board[2];
-        pawns &= clearFile[0];
+        pawns &= clearFile[7];
         long rightMoves = pawns >>> 7;
         return rightMoves & board[0];
     }
@@ -455,7 +877,7 @@ class Board {
      *  Does not calculate en passant */
     public static long wPawnLeftCaptures(long[] board) {
         long pawns = board[0] & board[2];
-        pawns &= clearFile[7];
+        pawns &= clearFile[0];
         long leftMoves = pawns << 7;
         return leftMoves & board[1];
     }
@@ -464,37 +886,233 @@ class Board {
      *  Does not calculate en passant */
     public static long bPawnLeftCaptures(long[] board) {
         long pawns = board[1] & board[2];
-        pawns &= clearFile[7];
+        pawns &= clearFile[0];
         long leftMoves = pawns >>> 9;
-        return leftMoves * board[0];
+        return leftMoves & board[0];
     }
 
     /** Returns the state representing all moves the rook on SQUARE can make, including captures.*/
     public static long rookMoves(long[] board, int square, Color player) {
-        long uncheckedMoves = rayAttack(board, square, 1) | rayAttack(board, square, -1) | rayAttack(board, square, 8) | rayAttack(board, square, -8);
+        long uncheckedMoves = slideAttacks(board, square, ORTHOGONALS);
         //ray attack includes an obstacle at the end, possibly of the same color
         return uncheckedMoves & ~board[player.index()];
     }
 
     /** Returns the state representing all moves the bishop on SQUARE can make, including captures.*/
     public static long bishopMoves(long[] board, int square, Color player) {
-        long uncheckedMoves = rayAttack(board, square, -7) | rayAttack(board, square, 7) | rayAttack(board, square, -9) | rayAttack(board, square, 9);
+        long uncheckedMoves = slideAttacks(board, square, DIAGONALS);
         return uncheckedMoves & ~board[player.index()];
     }
 
 
+    /** Returns the state representing all moves the knight on SQUARE can make, including captures.*/
+    public static long knightMoves(long[] board, int square, Color player) {
+        return knightAttacks[square] & ~board[player.index()];
+    }
+
     /** Returns the state representing all moves the queen on SQUARE can make, including captures. */
     public static long queenMoves(long[] board, int square, Color player) {
-        long uncheckedMoves = rayAttack(board, square, -7) | rayAttack(board, square, 7) | rayAttack(board, square, -9) | rayAttack(board, square, 9) | rayAttack(board, square, 1) | rayAttack(board, square, -1) | rayAttack(board, square, 8) | rayAttack(board, square, -8);
+        long uncheckedMoves = slideAttacks(board, square, ORIENTATIONS);
         return uncheckedMoves & ~board[player.index()];
     }
 
+    /** Returns the state representing all moves the king of PLAYER can make, including captures.
+     *  Squares next to the enemy king are left out since kings can never touch.*/
     public static long kingMoves(long[] board, Color player) {
-        //disclaimer: this is drunk coding all of the bit hacking is probably wrong
         long king = board[player.index()] & board[6];
-        long rect = (king & maskFile[0] << 1) & (king << 8) & (king & maskFile[7] >>> 1) & (king >>> 8);
-        long diag = (king & maskFile[7] << 7) & (king & maskFile[0] << 9) & (king & maskFile[0] >>> 7) & (king & maskFile[7] >>> 9);
-        return rect & diag;
+        long enemyKing = board[player.opposite().index()] & board[6];
+        if (king == 0) {
+            return 0;
+        }
+        long moves = kingAttacks[bitscanLSD(king)] & ~board[player.index()];
+        if (enemyKing != 0) {
+            moves &= ~kingAttacks[bitscanLSD(enemyKing)];
+        }
+        return moves;
+    }
+
+    /** Returns the state of all pieces, of either color, attacking SQUARE.
+     *  Sliders are stopped by the occupancy of BOARD, so clearing a piece
+     *  from the color bitboards uncovers whatever stands behind it. */
+    public static long attackersTo(long[] board, int square) {
+        long attackers = (pawnSources(square, WHITE) & board[0] & board[2])
+            | (pawnSources(square, BLACK) & board[1] & board[2]);
+        attackers |= knightAttacks[square] & board[4];
+        attackers |= kingAttacks[square] & board[6];
+        attackers |= slideAttacks(board, square, DIAGONALS) & (board[3] | board[7]);
+        attackers |= slideAttacks(board, square, ORTHOGONALS) & (board[5] | board[7]);
+        return attackers & occupancy(board);
+    }
+
+    /** True iff PLAYER's king is attacked */
+    public static boolean inCheck(long[] board, Color player) {
+        long king = board[player.index()] & board[6];
+        return king != 0 && isAttacked(board, bitscanLSD(king), player.opposite());
+    }
+
+    /** Returns the state of the enemy pieces giving check to PLAYER's king.
+     *  Two bits set means double check, where only a king move helps. */
+    public static long checkers(long[] board, Color player) {
+        long king = board[player.index()] & board[6];
+        if (king == 0) {
+            return 0;
+        }
+        return attackersTo(board, bitscanLSD(king)) & board[player.opposite().index()];
+    }
+
+    /** True iff some piece of PLAYER attacks SQUARE.
+     *  Cheaper than attackersTo when only a yes or no is needed, since
+     *  it gives up at the first hit and tries the sliders last. */
+    public static boolean isAttacked(long[] board, int square, Color player) {
+        long mine = board[player.index()];
+        if ((knightAttacks[square] & board[4] & mine) != 0) {
+            return true;
+        }
+        if ((kingAttacks[square] & board[6] & mine) != 0) {
+            return true;
+        }
+        if ((pawnSources(square, player) & board[2] & mine) != 0) {
+            return true;
+        }
+        if ((slideAttacks(board, square, DIAGONALS) & (board[3] | board[7]) & mine) != 0) {
+            return true;
+        }
+        return (slideAttacks(board, square, ORTHOGONALS) & (board[5] | board[7]) & mine) != 0;
+    }
+
+    /** True iff M checks the opponent, directly or by uncovering a slider.
+     *  Looks out from the enemy king over the occupancy M leaves behind:
+     *  the moved piece checks if it lands on one of the king's lines of its
+     *  kind, and any other slider of the mover now seeing the king was
+     *  uncovered. BOARD itself is never played on. */
+    public static boolean givesCheck(long[] board, Move m) {
+        Color player = m.isWhite() ? WHITE : BLACK;
+        long king = board[player.opposite().index()] & board[6];
+        if (king == 0) {
+            return false;
+        }
+        int k = bitscanLSD(king);
+        long from = 1L << m.getCoords()[0];
+        int square = m.getCoords()[1];
+        long to = 1L << square;
+        long occ = (occupancy(board) & ~from) | to;
+        long diag = slideAttacks(occ, k, DIAGONALS);
+        long rect = slideAttacks(occ, k, ORTHOGONALS);
+        switch (m.getPiece()) {
+        case 2:
+            if ((pawnAttacksFrom(square, player) & king) != 0) {
+                return true;
+            }
+            break;
+        case 3:
+            if ((diag & to) != 0) {
+                return true;
+            }
+            break;
+        case 4:
+            if ((knightAttacks[square] & king) != 0) {
+                return true;
+            }
+            break;
+        case 5:
+            if ((rect & to) != 0) {
+                return true;
+            }
+            break;
+        case 7:
+            if (((diag | rect) & to) != 0) {
+                return true;
+            }
+            break;
+        default:
+            break;
+        }
+        long others = board[player.index()] & ~from;
+        return (((diag & (board[3] | board[7])) | (rect & (board[5] | board[7]))) & others) != 0;
+    }
+
+    /** If the piece on SQUARE is pinned to its own king, returns the
+     *  squares it may still stand on: the line from the king out to the
+     *  pinning slider, the pinner included. Returns 0 if it isn't pinned. */
+    public static long pinRay(long[] board, int square) {
+        Color player = colorAt(board, square);
+        if (player == null || pieceAt(board, square) == 6) {
+            return 0;
+        }
+        long king = board[player.index()] & board[6];
+        if (king == 0) {
+            return 0;
+        }
+        int k = bitscanLSD(king);
+        long bit = 1L << square;
+        long occ = occupancy(board);
+        for (int dir : ORIENTATIONS) {
+            long toPiece = rayAttack(board, k, dir);
+            if ((toPiece & occ) != bit) {
+                continue;
+            }
+            long beyond = rayAttack(board, square, dir);
+            long pinner = beyond & board[player.opposite().index()];
+            boolean diagonal = dir == 7 || dir == -7 || dir == 9 || dir == -9;
+            long sliders = diagonal ? board[3] | board[7] : board[5] | board[7];
+            if ((pinner & sliders) != 0) {
+                return toPiece | beyond;
+            }
+            return 0;
+        }
+        return 0;
+    }
+
+    /** Returns the squares from which a pawn of PLAYER would attack SQUARE.
+     *  Those are exactly the squares an enemy pawn on SQUARE attacks. */
+    private static long pawnSources(int square, Color player) {
+        return pawnAttacks[player.opposite().index()][square];
+    }
+
+    /** Returns the union of the squares the PAWNS of PLAYER attack.
+     *  Pawns on the edge files only attack inwards. */
+    public static long pawnAttacks(long pawns, Color player) {
+        long east = pawns & clearFile[7];
+        long west = pawns & clearFile[0];
+        if (player == WHITE) {
+            return (east << 9) | (west << 7);
+        } else {
+            return (east >>> 7) | (west >>> 9);
+        }
+    }
+
+    /** Returns the squares a pawn of PLAYER on SQUARE, 0-63, attacks */
+    public static long pawnAttacksFrom(int square, Color player) {
+        return pawnAttacks[player.index()][square];
+    }
+
+    /** Returns the squares a knight on SQUARE, 0-63, attacks */
+    public static long knightAttacks(int square) {
+        return knightAttacks[square];
+    }
+
+    /** Returns the squares a king on SQUARE, 0-63, attacks */
+    public static long kingAttacks(int square) {
+        return kingAttacks[square];
+    }
+
+    /** Returns the ring of squares around PLAYER's king, plus the three
+     *  squares one further rank ahead of it, where a pawn storm or an
+     *  attacking piece will show up first. */
+    public static long kingRing(long[] board, Color player) {
+        long king = board[player.index()] & board[6];
+        if (king == 0) {
+            return 0;
+        }
+        int square = bitscanLSD(king);
+        int rank = square / 8;
+        long ring = kingAttacks[square];
+        if (player == WHITE && rank < 7) {
+            ring |= (ring & maskRank[rank + 1]) << 8;
+        } else if (player == BLACK && rank > 0) {
+            ring |= (ring & maskRank[rank - 1]) >>> 8;
+        }
+        return ring;
     }
 
     /** Returns the state with all of the bits from the starting point exclusive
@@ -530,38 +1148,59 @@ class Board {
         case (7):
             return northWe[square];
         default:
-            throw new Exception("Orientation is not an allowed value");
+            throw new IllegalArgumentException("Orientation is not an allowed value");
         }
     }
 
-    /** Called by the constructor. Fills in our ray tables */
-    private void initRays() {
+    /** Returns every allowed value of orientation, clockwise from north.
+     *  The array is a copy, so callers are free to change it. */
+    public static int[] orientations() {
+        return ORIENTATIONS.clone();
+    }
+
+    /** Returns a copy of the orientations a bishop slides along */
+    public static int[] diagonals() {
+        return DIAGONALS.clone();
+    }
+
+    /** Returns a copy of the orientations a rook slides along */
+    public static int[] orthogonals() {
+        return ORTHOGONALS.clone();
+    }
+
+    /** Returns the orientation pointing the opposite way to ORIENTATION */
+    public static int antipode(int orientation) {
+        return -orientation;
+    }
+
+    /** Called by the static initializer. Fills in our ray tables */
+    private static void initRays() {
         for (int rank = 0; rank < 8; rank++) {
             for (int file = 0; file < 8; file++) {
                 int ind = rank * 8 + file;
                 long vertical = maskFile[file] & clearRank[rank];
-                long horizontal = maskRank[rank] & clearFile[rank];
-                north[ind] = rankRange(rank, 7) & vertical;
+                long horizontal = maskRank[rank] & clearFile[file];
+                north[ind] = rankRange(rank, 8) & vertical;
                 south[ind] = rankRange(0, rank) & vertical;
-                west[ind] = fileRank(0, file) & horizontal;
-                east[ind] = fileRank(file, 7) & horizontal;
+                west[ind] = fileRange(0, file) & horizontal;
+                east[ind] = fileRange(file, 8) & horizontal;
             }
         }
         for (int i = 0; i < 64; i++) {
-            northEa[i] = (1 << i);
-            northWe[i] = (1 << i);
-            southEa[i] = (1 << i);
-            southWe[i] = (1 << i);
+            northEa[i] = (1L << i);
+            northWe[i] = (1L << i);
+            southEa[i] = (1L << i);
+            southWe[i] = (1L << i);
             for (int j = 0; j < 8; j++) {
                 northEa[i] |= (northEa[i] & clearFile[7]) << 9;
                 northWe[i] |= (northWe[i] & clearFile[0]) << 7;
-                southEa[i] |= (southEa[i] & clearFile[7]) << -7;
-                southWe[i] |= (southWe[i] & clearFile[0]) << -9;
+                southEa[i] |= (southEa[i] & clearFile[7]) >>> 7;
+                southWe[i] |= (southWe[i] & clearFile[0]) >>> 9;
             }
-            northEa[i] &= ~(1 << i);
-            northWe[i] &= ~(1 << i);
-            southEa[i] &= ~(1 << i);
-            southWe[i] &= ~(1 << i);
+            northEa[i] &= ~(1L << i);
+            northWe[i] &= ~(1L << i);
+            southEa[i] &= ~(1L << i);
+            southWe[i] &= ~(1L << i);
         }
     }
 
@@ -581,10 +1220,10 @@ class Board {
      *            -17  -15
      *        soSoWe    soSoEa
      **/
-    private void initKnightAttacks() {
+    private static void initKnightAttacks() {
         for (int s = 0; s < 64; s++) {
-            long knight = (1 << s);
-            knightAttacks[s] = knight;
+            long knight = (1L << s);
+            knightAttacks[s] = 0;
             knightAttacks[s] |= (knight << 17) & clearFile[0];
             knightAttacks[s] |= (knight << 10) & (clearFile[0] & clearFile[1]);
             knightAttacks[s] |= (knight >>> 6) & (clearFile[0] & clearFile[1]);
@@ -596,6 +1235,15 @@ class Board {
         }
     }
     
+    /** Fills the king lookup table with the (up to) eight neighbours of each square */
+    private static void initKingAttacks() {
+        for (int s = 0; s < 64; s++) {
+            long king = 1L << s;
+            long row = king | ((king << 1) & clearFile[0]) | ((king >>> 1) & clearFile[7]);
+            kingAttacks[s] = (row | (row << 8) | (row >>> 8)) & ~king;
+        }
+    }
+
     /** Ranks correspond to numbers. RANK corresponds to letters
      *  0 : A
      *  1 : B
@@ -603,7 +1251,7 @@ class Board {
      *  Returns the state with RANK filled
      *  Used to initialize tables*/
     private static long fillRank(int rank) {
-        long result = 11111111;
+        long result = 0xFFL;
         return (result << rank * 8);
     }
 
@@ -611,13 +1259,15 @@ class Board {
     private static long fillFile(int file) {
         long result = 0;
         for (int i = 0; i < 8; i++) {
-            result |= (file << i * 8); 
+            result |= (1L << file << i * 8);
         }
         return result;
     }
 
-    /** Returns state filled from rank [start to end) */ 
-    private static long rankRange(int start, int end) {
+    /** Returns state filled from rank [start to end)
+     *  0 <= START <= END <= 8, so rankRange(0, 8) is the full board */
+    static long rankRange(int start, int end) {
+        checkRange(start, end);
         long result = 0;
         for (int i = start; i < end; i++) {
             result |= fillRank(i);
@@ -625,8 +1275,10 @@ class Board {
         return result;
     }
 
-    /** Returns state filled from file [start to end) */ 
-    private static long fileRange(int start, int end) {
+    /** Returns state filled from file [start to end)
+     *  0 <= START <= END <= 8, so fileRange(0, 8) is the full board */
+    static long fileRange(int start, int end) {
+        checkRange(start, end);
         long result = 0;
         for (int i = start; i < end; i++) {
             result |= fillFile(i);
@@ -634,9 +1286,40 @@ class Board {
         return result;
     }
 
+    /** Rejects a rank or file range that isn't within [0, 8) */
+    private static void checkRange(int start, int end) {
+        if (start < 0 || end > 8 || start > end) {
+            throw new IllegalArgumentException("Bad range [" + start + ", " + end + ")");
+        }
+    }
+
+    /** Rejects a SQUARE that isn't on the board, 0-63. The shift in
+     *  1L << square would otherwise quietly wrap it around. */
+    static void checkSquare(int square) {
+        if (square < 0 || square > 63) {
+            throw new IllegalArgumentException("Square off the board: " + square);
+        }
+    }
+
+    /** Letter for each piece as drawn by render, indexed like the bitboard */
+    private static final String PIECE_SYMBOLS = ".?PBNRKQ";
+
+    /** Every allowed value of orientation, see ray, clockwise from north */
+    private static final int[] ORIENTATIONS = {8, 9, 1, -7, -8, -9, -1, 7};
+    /** The orientations a bishop slides along */
+    private static final int[] DIAGONALS = {9, -7, -9, 7};
+    /** The orientations a rook slides along */
+    private static final int[] ORTHOGONALS = {8, 1, -8, -1};
+
     /** 64 wide array with knight attacks for each square*/
     private static  long[] knightAttacks;
 
+    /** 64 wide array with king attacks for each square */
+    private static long[] kingAttacks;
+
+    /** Pawn attacks for each square, the first index is the color's index */
+    private static long[][] pawnAttacks;
+
     /** Each of the following four tables is of size 8.
      *  The entry at i performs the eponymous operation when &ed with a state*/
     private static long[] clearRank;
@@ -655,7 +1338,45 @@ class Board {
     private static long[] northWe;
 
 
-    private static long[] hMultipliers = new long[9];
+    private static long[] hMultipliers;
+
+    /** Builds all of the lookup tables once, when the class is loaded,
+     *  so the static methods can be used without constructing a Board. */
+    static {
+        clearRank = new long[8];
+        clearFile = new long[8];
+        maskRank = new long[8];
+        maskFile = new long[8];
+        for (int i = 0; i < 8; i++) {
+            maskRank[i] = fillRank(i);
+            maskFile[i] = fillFile(i);
+            clearRank[i] = ~maskRank[i];
+            clearFile[i] = ~maskFile[i];
+        }
+        north = new long[64];
+        northEa = new long[64];
+        west = new long[64];
+        southWe = new long[64];
+        south = new long[64];
+        southEa = new long[64];
+        east = new long[64];
+        northWe = new long[64];
+        initRays();
+        knightAttacks = new long[64];
+        initKnightAttacks();
+        kingAttacks = new long[64];
+        initKingAttacks();
+        pawnAttacks = new long[2][64];
+        for (int sq = 0; sq < 64; sq++) {
+            pawnAttacks[0][sq] = pawnAttacks(1L << sq, WHITE);
+            pawnAttacks[1][sq] = pawnAttacks(1L << sq, BLACK);
+        }
+        Random rgen = new Random();
+        hMultipliers = new long[9];
+        for (int k = 0; k < 9; k++) {
+            hMultipliers[k] = rgen.nextLong();
+        }
+    }
 
     /** Utility function for the move generator */
     private static int[][] arrListToPrim(ArrayList<int[]> m) {
@@ -0,0 +1,41 @@
+package deciduous;
+
+import static deciduous.Color.*;
+
+/** Fluent construction of a board, mostly for setting up test positions.
+ *  Starts from an empty board with WHITE to move, e.g.
+ *  new BoardBuilder().piece(4, 6, WHITE).piece(60, 6, BLACK).build()
+ *  @author Andrew Berger */
+class BoardBuilder {
+
+    BoardBuilder() {
+        _board = new long[9];
+        _toMove = WHITE;
+    }
+
+    /** Puts PIECE, an integer 2-7, of PLAYER on SQUARE, 0-63.
+     *  Throws IllegalArgumentException for a square off the board. */
+    BoardBuilder piece(int square, int piece, Color player) {
+        Board.checkSquare(square);
+        Board.setPiece(_board, square, piece, player);
+        return this;
+    }
+
+    /** Sets the side to move, which build validates the position against */
+    BoardBuilder sideToMove(Color player) {
+        _toMove = player;
+        return this;
+    }
+
+    /** Returns the finished board.
+     *  Throws IllegalArgumentException if Board.validate rejects it. */
+    long[] build() {
+        Board.validate(_board, _toMove);
+        return _board.clone();
+    }
+
+    /** The board under construction */
+    private long[] _board;
+    /** Who moves first in the built position */
+    private Color _toMove;
+}
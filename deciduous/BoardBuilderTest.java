@@ -0,0 +1,56 @@
+package deciduous;
+
+import static deciduous.Color.*;
+import static deciduous.UnitTest.*;
+
+/** Tests of fluent position setup.
+ *  @author Andrew Berger */
+class BoardBuilderTest {
+    /** A king and queen against a king lands on exactly the right bitboards */
+    static void testKingAndQueenAgainstKing() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("d1"), 7, WHITE)
+            .piece(sq("e8"), 6, BLACK)
+            .build();
+        assertEquals("white", (1L << sq("e1")) | (1L << sq("d1")), b[0]);
+        assertEquals("black", 1L << sq("e8"), b[1]);
+        assertEquals("kings", (1L << sq("e1")) | (1L << sq("e8")), b[6]);
+        assertEquals("queens", 1L << sq("d1"), b[7]);
+        assertEquals("white king", sq("e1"), Board.bitscanLSD(b[0] & b[6]));
+        assertEquals("black king", sq("e8"), Board.bitscanLSD(b[1] & b[6]));
+        for (int p = 2; p <= 5; p++) {
+            assertEquals("no piece " + p, 0, b[p]);
+        }
+    }
+
+    /** build refuses a position validate refuses, here one with the side
+     *  that just moved still in check */
+    static void testBuildValidates() {
+        BoardBuilder builder = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e2"), 7, WHITE)
+            .piece(sq("e8"), 6, BLACK);
+        try {
+            builder.build();
+            fail("black in check with white to move accepted");
+        } catch (IllegalArgumentException e) {
+            builder.sideToMove(BLACK).build();
+        }
+    }
+
+    /** A square off the board is refused rather than wrapped round onto
+     *  a1 or h8, and the position built so far is kept. */
+    static void testPieceRejectsOffBoardSquares() {
+        BoardBuilder builder = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e8"), 6, BLACK);
+        for (int square : new int[] {64, -1}) {
+            try {
+                builder.piece(square, 5, WHITE);
+                fail("square " + square + " accepted");
+            } catch (IllegalArgumentException e) {
+                assertTrue(e.getMessage(), e.getMessage().contains("off the board"));
+            }
+        }
+        long[] b = builder.build();
+        assertEquals("no rooks", 0, b[5]);
+    }
+}
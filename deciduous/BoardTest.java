@@ -0,0 +1,964 @@
+package deciduous;
+
+import java.util.Arrays;
+import java.util.List;
+import java.util.Random;
+import java.util.Set;
+import java.util.TreeSet;
+
+import static deciduous.Color.*;
+import static deciduous.UnitTest.*;
+
+/** Tests of the bitboard representation and move generation.
+ *  @author Andrew Berger */
+class BoardTest {
+    /** withMove leaves its argument alone and agrees with make on a copy */
+    static void testWithMoveLeavesParentUntouched() {
+        long[] b = startPosition();
+        Move m = find(Board.generateMoves(b, WHITE), "e2e4");
+        long[] child = Board.withMove(b, m);
+        assertTrue("parent untouched", Arrays.equals(startPosition(), b));
+        long[] copy = startPosition();
+        Board.make(copy, m);
+        assertTrue("same as make", Arrays.equals(copy, child));
+        assertEquals("pawn on e4", 2, Board.pieceAt(child, sq("e4")));
+    }
+
+    /** Setting up the initial position one square at a time gives the
+     *  same bitboards as the Defaults constants. */
+    static void testSetPieceBuildsStartPosition() {
+        int[] backRank = {5, 4, 3, 7, 6, 3, 4, 5};
+        long[] b = new long[9];
+        for (int file = 0; file < 8; file++) {
+            Board.setPiece(b, file, backRank[file], WHITE);
+            Board.setPiece(b, 8 + file, 2, WHITE);
+            Board.setPiece(b, 48 + file, 2, BLACK);
+            Board.setPiece(b, 56 + file, backRank[file], BLACK);
+        }
+        assertTrue("start position", Arrays.equals(startPosition(), b));
+        Board.removePiece(b, sq("e2"));
+        assertEquals("e2 empty", 0, Board.pieceAt(b, sq("e2")));
+        assertEquals("one pawn fewer", 15, Long.bitCount(b[2]));
+        assertEquals("one white piece fewer", 15, Long.bitCount(b[0]));
+    }
+
+    /** pieceAt and colorAt report pieces in absolute terms, and nothing
+     *  on an empty square. */
+    static void testPieceAtStartPosition() {
+        long[] b = startPosition();
+        assertEquals("e1 piece", 6, Board.pieceAt(b, sq("e1")));
+        assertEquals("e1 color", WHITE, Board.colorAt(b, sq("e1")));
+        assertEquals("d8 piece", 7, Board.pieceAt(b, sq("d8")));
+        assertEquals("d8 color", BLACK, Board.colorAt(b, sq("d8")));
+        assertEquals("e4 piece", 0, Board.pieceAt(b, sq("e4")));
+        assertEquals("e4 color", null, Board.colorAt(b, sq("e4")));
+    }
+
+    /** The lookup tables are built when Board is loaded, no constructor needed */
+    static void testTablesBuiltOnLoad() {
+        assertEquals("knight on a1", (1L << sq("b3")) | (1L << sq("c2")), Board.knightAttacks(0));
+        assertEquals("east of a1", 0xFEL, Board.ray(0, 1));
+        assertEquals("pawn attacks from e2", (1L << sq("d3")) | (1L << sq("f3")),
+                     Board.pawnAttacksFrom(sq("e2"), WHITE));
+    }
+
+    /** antipode of every orientation round-trips, and the accessors hand
+     *  out copies that can't disturb the tables. */
+    static void testOrientationsAntipode() {
+        int[] all = Board.orientations();
+        assertEquals("eight orientations", 8, all.length);
+        for (int dir : all) {
+            assertEquals("antipode of " + dir, -dir, Board.antipode(dir));
+            assertEquals("round trip " + dir, dir, Board.antipode(Board.antipode(dir)));
+        }
+        all[0] = 0;
+        assertEquals("copy", 8, Board.orientations()[0]);
+        assertEquals("diagonals", 4, Board.diagonals().length);
+        assertEquals("orthogonals", 4, Board.orthogonals().length);
+    }
+
+    /** A rook behind its own knight x-rays through it onto the enemy king */
+    static void testXrayRookSeesKingBehindBlocker() {
+        long[] b = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("e1"), 5, WHITE).piece(sq("e4"), 4, WHITE)
+            .build();
+        long own = b[0];
+        long xray = Board.xrayRookAttacks(b, sq("e1"), own);
+        assertTrue("sees the king", (xray & (1L << sq("e8"))) != 0);
+        assertTrue("sees e5", (xray & (1L << sq("e5"))) != 0);
+        assertFalse("not the blocker itself", (xray & (1L << sq("e4"))) != 0);
+        assertEquals("nothing to x-ray along the rank", 0,
+                     Board.xrayRookAttacks(b, sq("e1"), own) & Board.ray(sq("e1"), 1));
+    }
+
+    /** The same holds on the diagonals for a bishop */
+    static void testXrayBishopSeesKingBehindBlocker() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("h8"), 6, BLACK)
+            .piece(sq("a1"), 3, WHITE).piece(sq("c3"), 2, WHITE)
+            .build();
+        long xray = Board.xrayBishopAttacks(b, sq("a1"), b[0]);
+        assertTrue("sees the king", (xray & (1L << sq("h8"))) != 0);
+    }
+
+    /** Returns a random, not necessarily legal, position drawn from RANDOM:
+     *  one king each plus a dozen other pieces on random squares. */
+    private static long[] randomBoard(Random random) {
+        long[] b = new long[9];
+        Board.setPiece(b, random.nextInt(64), 6, WHITE);
+        int square;
+        do {
+            square = random.nextInt(64);
+        } while (Board.pieceAt(b, square) != 0);
+        Board.setPiece(b, square, 6, BLACK);
+        for (int i = 0; i < 12; i++) {
+            square = random.nextInt(64);
+            if (Board.pieceAt(b, square) != 6) {
+                int piece = new int[] {2, 3, 4, 5, 7}[random.nextInt(5)];
+                Board.setPiece(b, square, piece, random.nextBoolean() ? WHITE : BLACK);
+            }
+        }
+        return b;
+    }
+
+    /** isAttacked agrees with attackersTo on every square of random positions */
+    static void testIsAttackedAgreesWithAttackersTo() {
+        Random random = new Random(1318);
+        for (int n = 0; n < 50; n++) {
+            long[] b = randomBoard(random);
+            for (int s = 0; s < 64; s++) {
+                long attackers = Board.attackersTo(b, s);
+                for (Color c : Color.values()) {
+                    assertEquals("square " + s + " by " + c + " in\n" + Board.render(b, 0),
+                                 (attackers & b[c.index()]) != 0, Board.isAttacked(b, s, c));
+                }
+            }
+        }
+    }
+
+    /** givesCheck spots a direct knight check, a discovered rook check,
+     *  and a quiet move that checks nothing. */
+    static void testGivesCheck() {
+        long[] b = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("e1"), 5, WHITE).piece(sq("e4"), 4, WHITE)
+            .piece(sq("h2"), 2, WHITE)
+            .build();
+        List<Move> moves = Board.generateMoves(b, WHITE);
+        assertTrue("Nd6 checks directly", Board.givesCheck(b, find(moves, "e4d6")));
+        assertTrue("Nc3 uncovers the rook", Board.givesCheck(b, find(moves, "e4c3")));
+        assertFalse("h3 checks nothing", Board.givesCheck(b, find(moves, "h2h3")));
+        assertFalse("Re2 stays behind the knight", Board.givesCheck(b, find(moves, "e1e2")));
+    }
+
+    /** givesCheck agrees with playing the move and looking, on random positions */
+    static void testGivesCheckAgreesWithMake() {
+        Random random = new Random(1321);
+        for (int n = 0; n < 200; n++) {
+            long[] b = randomBoard(random);
+            if (Board.inCheck(b, BLACK)) {
+                continue;
+            }
+            for (Move m : Board.generateMoves(b, WHITE)) {
+                long[] after = Board.withMove(b, m);
+                assertEquals(m + " in\n" + Board.render(b, 0),
+                             Board.inCheck(after, BLACK), Board.givesCheck(b, m));
+            }
+        }
+    }
+
+    /** A pawn that has already moved only gets the single push */
+    static void testPawnOnThirdRankHasNoDoublePush() {
+        long[] b = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("h8"), 6, BLACK)
+            .piece(sq("e3"), 2, WHITE)
+            .build();
+        List<Move> pushes = Board.generatePawnMoves(b, WHITE);
+        assertEquals("one push", 1, pushes.size());
+        assertEquals("e3e4", "e3e4", pushes.get(0).toString());
+    }
+
+    /** Pawns on their starting rank get both pushes, black ones too,
+     *  unless the square in front is taken. */
+    static void testPawnDoublePushes() {
+        long[] b = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("h8"), 6, BLACK)
+            .piece(sq("e2"), 2, WHITE).piece(sq("d2"), 2, WHITE).piece(sq("d3"), 4, WHITE)
+            .piece(sq("c7"), 2, BLACK)
+            .build();
+        List<Move> white = Board.generatePawnMoves(b, WHITE);
+        assertEquals("white pushes", 2, white.size());
+        assertTrue("e2e3", find(white, "e2e3") != null);
+        assertTrue("e2e4", find(white, "e2e4") != null);
+        List<Move> black = Board.generatePawnMoves(b, BLACK);
+        assertEquals("black pushes", 2, black.size());
+        assertTrue("c7c6", find(black, "c7c6") != null);
+        assertTrue("c7c5", find(black, "c7c5") != null);
+    }
+
+    /** Move counts from the initial position match the published perft
+     *  figures, which don't involve castling, en passant or promotion yet. */
+    static void testPerftFromStart() {
+        long[] b = startPosition();
+        assertEquals("perft 1", 20, perft(b, WHITE, 1));
+        assertEquals("perft 2", 400, perft(b, WHITE, 2));
+        assertEquals("perft 3", 8902, perft(b, WHITE, 3));
+        assertTrue("board restored", Arrays.equals(startPosition(), b));
+    }
+
+    /** Counts the legal move sequences of length DEPTH from B with P to move */
+    private static long perft(long[] b, Color p, int depth) {
+        if (depth == 0) {
+            return 1;
+        }
+        long count = 0;
+        for (Move m : Board.generateMoves(b, p)) {
+            Board.make(b, m);
+            if (!Board.inCheck(b, p)) {
+                count += perft(b, p.opposite(), depth - 1);
+            }
+            Board.unmake(b, m);
+        }
+        return count;
+    }
+
+    /** bitscanLSD and bitscanMSD on single bits, two bits and nothing */
+    static void testBitscans() {
+        assertEquals("lsd of bit 0", 0, Board.bitscanLSD(1L));
+        assertEquals("lsd of bit 63", 63, Board.bitscanLSD(1L << 63));
+        assertEquals("lsd of two bits", 3, Board.bitscanLSD((1L << 3) | (1L << 40)));
+        assertEquals("msd of bit 0", 0, Board.bitscanMSD(1L));
+        assertEquals("msd of bit 63", 63, Board.bitscanMSD(1L << 63));
+        assertEquals("msd of two bits", 40, Board.bitscanMSD((1L << 3) | (1L << 40)));
+        assertEquals("msd of nothing", -1, Board.bitscanMSD(0));
+    }
+
+    /** The initial position survives a trip through pieceAt/colorAt and fromSquares */
+    static void testFromSquaresRoundTrip() {
+        long[] start = startPosition();
+        int[] pieces = new int[64];
+        Color[] colors = new Color[64];
+        for (int s = 0; s < 64; s++) {
+            pieces[s] = Board.pieceAt(start, s);
+            colors[s] = Board.colorAt(start, s);
+        }
+        assertTrue("round trip", Arrays.equals(start, Board.fromSquares(pieces, colors)));
+    }
+
+    /** fromSquares rejects pieces out of range, pieces without a color,
+     *  layouts of the wrong size and a missing king. */
+    static void testFromSquaresRejectsBadLayouts() {
+        int[] pieces = new int[64];
+        Color[] colors = new Color[64];
+        pieces[sq("e1")] = 6;
+        colors[sq("e1")] = WHITE;
+        pieces[sq("e8")] = 6;
+        colors[sq("e8")] = BLACK;
+        Board.fromSquares(pieces, colors);
+        pieces[sq("a2")] = 8;
+        colors[sq("a2")] = WHITE;
+        assertRejected("piece 8", pieces, colors);
+        pieces[sq("a2")] = 1;
+        assertRejected("piece 1", pieces, colors);
+        pieces[sq("a2")] = 2;
+        colors[sq("a2")] = null;
+        assertRejected("no color", pieces, colors);
+        pieces[sq("a2")] = 0;
+        assertRejected("short layout", new int[63], new Color[63]);
+        pieces[sq("e8")] = 0;
+        assertRejected("no black king", pieces, colors);
+    }
+
+    /** Fails with MSG unless fromSquares refuses PIECES and COLORS */
+    private static void assertRejected(String msg, int[] pieces, Color[] colors) {
+        try {
+            Board.fromSquares(pieces, colors);
+            fail(msg + " accepted");
+        } catch (IllegalArgumentException e) {
+            return;
+        }
+    }
+
+    /** Fails with MSG unless validate refuses B with P to move */
+    private static void assertInvalid(String msg, long[] b, Color p) {
+        try {
+            Board.validate(b, p);
+            fail(msg + " accepted");
+        } catch (IllegalArgumentException e) {
+            return;
+        }
+    }
+
+    /** validate accepts the initial position and each kind of broken one */
+    static void testValidate() {
+        Board.validate(startPosition(), WHITE);
+        long[] b = startPosition();
+        b[5] |= 1L << sq("e2");
+        assertInvalid("two pieces on e2", b, WHITE);
+        b = startPosition();
+        b[0] |= 1L << sq("e4");
+        assertInvalid("color without a piece", b, WHITE);
+        b = startPosition();
+        Board.removePiece(b, sq("e8"));
+        assertInvalid("no black king", b, WHITE);
+        b = startPosition();
+        Board.setPiece(b, sq("d1"), 6, WHITE);
+        assertInvalid("two white kings", b, WHITE);
+        b = startPosition();
+        Board.setPiece(b, sq("a8"), 2, BLACK);
+        assertInvalid("pawn on the last rank", b, WHITE);
+        b = new long[9];
+        Board.setPiece(b, sq("e1"), 6, WHITE);
+        Board.setPiece(b, sq("e8"), 6, BLACK);
+        Board.setPiece(b, sq("e4"), 5, WHITE);
+        Board.validate(b, BLACK);
+        assertInvalid("black in check with white to move", b, WHITE);
+    }
+
+    /** A knight in the center attacks eight squares, one in the corner two */
+    static void testKnightAndKingAttacks() {
+        assertEquals("knight on d4", 8, Long.bitCount(Board.knightAttacks(sq("d4"))));
+        assertEquals("knight on a1", 2, Long.bitCount(Board.knightAttacks(sq("a1"))));
+        assertEquals("king on d4", 8, Long.bitCount(Board.kingAttacks(sq("d4"))));
+        assertEquals("king on h8", 3, Long.bitCount(Board.kingAttacks(sq("h8"))));
+        assertEquals("knight on h8", 0, Board.knightAttacks(sq("h8")) & (1L << sq("h8")));
+    }
+
+    /** Edge pawns attack one square without wrapping, center pawns two */
+    static void testPawnAttacks() {
+        long aPawn = 1L << sq("a2");
+        long hPawn = 1L << sq("h2");
+        assertEquals("white a2", 1L << sq("b3"), Board.pawnAttacks(aPawn, WHITE));
+        assertEquals("white h2", 1L << sq("g3"), Board.pawnAttacks(hPawn, WHITE));
+        assertEquals("white e4", (1L << sq("d5")) | (1L << sq("f5")),
+                     Board.pawnAttacks(1L << sq("e4"), WHITE));
+        assertEquals("black a7", 1L << sq("b6"), Board.pawnAttacks(1L << sq("a7"), BLACK));
+        assertEquals("black h7", 1L << sq("g6"), Board.pawnAttacks(1L << sq("h7"), BLACK));
+        assertEquals("black e5", (1L << sq("d4")) | (1L << sq("f4")),
+                     Board.pawnAttacks(1L << sq("e5"), BLACK));
+        for (int s = 8; s < 56; s++) {
+            assertEquals("table white " + s, Board.pawnAttacks(1L << s, WHITE),
+                         Board.pawnAttacksFrom(s, WHITE));
+            assertEquals("table black " + s, Board.pawnAttacks(1L << s, BLACK),
+                         Board.pawnAttacksFrom(s, BLACK));
+        }
+    }
+
+    /** Pawns on the edge files never capture around the board onto the far file */
+    static void testPawnCapturesDontWrap() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("a2"), 2, WHITE).piece(sq("h3"), 2, WHITE)
+            .piece(sq("b3"), 4, BLACK).piece(sq("g4"), 4, BLACK)
+            .piece(sq("h2"), 4, BLACK).piece(sq("a5"), 4, BLACK)
+            .piece(sq("a7"), 2, BLACK).piece(sq("h6"), 2, BLACK)
+            .piece(sq("b6"), 4, WHITE).piece(sq("g5"), 4, WHITE)
+            .piece(sq("h5"), 4, WHITE).piece(sq("a6"), 4, WHITE)
+            .build();
+        assertEquals("white right", 1L << sq("b3"), Board.wPawnRightCaptures(b));
+        assertEquals("white left", 1L << sq("g4"), Board.wPawnLeftCaptures(b));
+        assertEquals("black right", 1L << sq("b6"), Board.bPawnRightCaptures(b));
+        assertEquals("black left", 1L << sq("g5"), Board.bPawnLeftCaptures(b));
+        List<Move> white = Board.generatePawnCaptures(b, WHITE);
+        assertEquals("two white captures", 2, white.size());
+        assertTrue("axb3", find(white, "a2b3") != null);
+        assertTrue("hxg4", find(white, "h3g4") != null);
+        List<Move> black = Board.generatePawnCaptures(b, BLACK);
+        assertEquals("two black captures", 2, black.size());
+        assertTrue("axb6", find(black, "a7b6") != null);
+        assertTrue("hxg5", find(black, "h6g5") != null);
+    }
+
+    /** The ring of a king on its own back rank corner is three squares
+     *  plus the two just in front of them; a king on the far edge has no
+     *  rank ahead, and a central one has all eight plus three ahead. */
+    static void testKingRing() {
+        long[] b = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("d5"), 6, BLACK)
+            .build();
+        long corner = Board.kingRing(b, WHITE);
+        assertEquals("a1 ring", 5, Long.bitCount(corner));
+        assertTrue("includes a3 and b3", (corner & (1L << sq("a3"))) != 0
+                   && (corner & (1L << sq("b3"))) != 0);
+        long central = Board.kingRing(b, BLACK);
+        assertEquals("d5 ring", 11, Long.bitCount(central));
+        assertEquals("d5 neighbours", 8, Long.bitCount(central & Board.kingAttacks(sq("d5"))));
+        long[] edge = new BoardBuilder()
+            .piece(sq("h8"), 6, WHITE).piece(sq("a1"), 6, BLACK)
+            .build();
+        assertEquals("h8 ring", 3, Long.bitCount(Board.kingRing(edge, WHITE)));
+    }
+
+    /** isLegal takes a knight move, refuses a piece that isn't there, a
+     *  king walking into check and squares off the board. */
+    static void testIsLegal() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("g1"), 4, WHITE).piece(sq("d8"), 5, BLACK)
+            .build();
+        long[] before = b.clone();
+        assertTrue("Nf3", Board.isLegal(b, new Move(new int[] {sq("g1"), sq("f3")}, 4, WHITE)));
+        assertFalse("no knight on b1",
+                    Board.isLegal(b, new Move(new int[] {sq("b1"), sq("c3")}, 4, WHITE)));
+        assertFalse("black knight", Board.isLegal(b, new Move(new int[] {sq("g1"), sq("f3")}, 4, BLACK)));
+        assertFalse("Kd1 into the rook",
+                    Board.isLegal(b, new Move(new int[] {sq("e1"), sq("d1")}, 6, WHITE)));
+        assertFalse("from 64", Board.isLegal(b, new Move(new int[] {64, 8}, 4, WHITE)));
+        assertFalse("to 64", Board.isLegal(b, new Move(new int[] {sq("g1"), 64}, 4, WHITE)));
+        assertFalse("negative", Board.isLegal(b, new Move(new int[] {-58, sq("f3")}, 4, WHITE)));
+        assertTrue("board untouched", Arrays.equals(before, b));
+    }
+
+    /** Fails with MSG unless rankRange and fileRange both refuse [START, END) */
+    private static void assertBadRange(String msg, int start, int end) {
+        try {
+            Board.rankRange(start, end);
+            fail(msg + " accepted by rankRange");
+        } catch (IllegalArgumentException e) {
+            assertTrue(msg, e.getMessage().startsWith("Bad range"));
+        }
+        try {
+            Board.fileRange(start, end);
+            fail(msg + " accepted by fileRange");
+        } catch (IllegalArgumentException e) {
+            assertTrue(msg, e.getMessage().startsWith("Bad range"));
+        }
+    }
+
+    /** The ranges are half open, so [0, 8) is the whole board, and bounds
+     *  outside it are refused up front. */
+    static void testRankAndFileRanges() {
+        assertEquals("all ranks", -1L, Board.rankRange(0, 8));
+        assertEquals("all files", -1L, Board.fileRange(0, 8));
+        assertEquals("first rank", Defaults.FIRST_RANK, Board.rankRange(0, 1));
+        assertEquals("a file", Defaults.A_FILE, Board.fileRange(0, 1));
+        assertEquals("empty", 0, Board.rankRange(3, 3));
+        assertBadRange("end 9", 0, 9);
+        assertBadRange("start -1", -1, 4);
+        assertBadRange("backwards", 5, 4);
+    }
+
+    /** generateChecks finds the only two checking moves and nothing else */
+    static void testGenerateChecks() {
+        long[] b = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("h8"), 6, BLACK)
+            .piece(sq("b2"), 5, WHITE).piece(sq("d4"), 2, WHITE)
+            .build();
+        List<Move> checks = Board.generateChecks(b, WHITE);
+        assertEquals("two checks", 2, checks.size());
+        assertTrue("Rb8", find(checks, "b2b8") != null);
+        assertTrue("Rh2", find(checks, "b2h2") != null);
+    }
+
+    /** A board doesn't differ from itself, and one move changes two squares */
+    static void testDiff() {
+        long[] b = startPosition();
+        assertEquals("same board", 0, Board.diff(b, startPosition()));
+        long[] after = Board.withMove(b, find(Board.generateMoves(b, WHITE), "g1f3"));
+        assertEquals("g1 and f3", (1L << sq("g1")) | (1L << sq("f3")), Board.diff(b, after));
+    }
+
+    /** With the kings two squares apart neither may step between them */
+    static void testKingsKeepApart() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e4"), 6, WHITE).piece(sq("e6"), 6, BLACK)
+            .build();
+        long between = (1L << sq("d5")) | (1L << sq("e5")) | (1L << sq("f5"));
+        assertEquals("white king", 0, Board.kingMoves(b, WHITE) & between);
+        assertEquals("black king", 0, Board.kingMoves(b, BLACK) & between);
+        assertEquals("five squares left", 5, Long.bitCount(Board.kingMoves(b, WHITE)));
+        for (Move m : Board.generateMoves(b, WHITE)) {
+            assertEquals(m + " next to the king", 0, (1L << m.getCoords()[1]) & between);
+        }
+    }
+
+    /** The initial position occupies 32 squares, its complement the other 32 */
+    static void testOccupancy() {
+        long occ = Board.occupancy(startPosition());
+        assertEquals("occupied", 32, Long.bitCount(occ));
+        assertEquals("empty", 32, Long.bitCount(~occ));
+        assertEquals("ranks 1, 2, 7 and 8",
+                     Defaults.rank(0) | Defaults.rank(1) | Defaults.rank(6) | Defaults.rank(7), occ);
+    }
+
+    /** Slides from SQUARE one step at a time along each (file, rank) step
+     *  of STEPS on B, stopping on the first occupied square, and returns
+     *  what it reached minus PLAYER's own pieces. The slow reference the
+     *  slider move sets are checked against. */
+    private static long slowSlide(long[] b, int square, Color player, int[][] steps) {
+        long reached = 0;
+        for (int[] step : steps) {
+            int file = square % 8 + step[0];
+            int rank = square / 8 + step[1];
+            while (file >= 0 && file < 8 && rank >= 0 && rank < 8) {
+                long bit = 1L << (rank * 8 + file);
+                reached |= bit;
+                if ((Board.occupancy(b) & bit) != 0) {
+                    break;
+                }
+                file += step[0];
+                rank += step[1];
+            }
+        }
+        return reached & ~b[player.index()];
+    }
+
+    /** rookMoves, bishopMoves and queenMoves match a square by square slide
+     *  on random positions, for every square of either color's pieces. */
+    static void testSliderMovesMatchSlowSlide() {
+        int[][] straight = {{0, 1}, {1, 0}, {0, -1}, {-1, 0}};
+        int[][] diagonal = {{1, 1}, {1, -1}, {-1, -1}, {-1, 1}};
+        int[][] both = {{0, 1}, {1, 0}, {0, -1}, {-1, 0}, {1, 1}, {1, -1}, {-1, -1}, {-1, 1}};
+        Random random = new Random(1377);
+        for (int n = 0; n < 100; n++) {
+            long[] b = randomBoard(random);
+            long occ = Board.occupancy(b);
+            while (occ != 0) {
+                int s = Board.bitscanLSD(occ);
+                Color c = Board.colorAt(b, s);
+                assertEquals("rook " + s, slowSlide(b, s, c, straight), Board.rookMoves(b, s, c));
+                assertEquals("bishop " + s, slowSlide(b, s, c, diagonal), Board.bishopMoves(b, s, c));
+                assertEquals("queen " + s, slowSlide(b, s, c, both), Board.queenMoves(b, s, c));
+                occ &= occ - 1;
+            }
+        }
+    }
+
+    /** True iff P is not in check and has no legal move on B, found the
+     *  slow way by trying every generated move. */
+    private static boolean slowStalemate(long[] b, Color p) {
+        if (Board.inCheck(b, p)) {
+            return false;
+        }
+        for (Move m : Board.generateMoves(b, p)) {
+            if (Board.isLegal(b, m)) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    /** isStalemate spots the classic queen stalemate, and its fast path
+     *  agrees with trying every move on random positions. */
+    static void testIsStalemate() {
+        long[] b = new BoardBuilder()
+            .piece(sq("h1"), 6, WHITE).piece(sq("b6"), 7, WHITE)
+            .piece(sq("a8"), 6, BLACK)
+            .sideToMove(BLACK)
+            .build();
+        assertTrue("Ka8 against Qb6", Board.isStalemate(b, BLACK));
+        assertFalse("white can move", Board.isStalemate(b, WHITE));
+        Random random = new Random(1379);
+        for (int n = 0; n < 300; n++) {
+            long[] r = randomBoard(random);
+            for (Color c : Color.values()) {
+                if (Board.inCheck(r, c.opposite())) {
+                    continue;
+                }
+                assertEquals(c + " in\n" + Board.render(r, 0), slowStalemate(r, c), Board.isStalemate(r, c));
+            }
+        }
+    }
+
+    /** A knight pinned by a rook may only stand on the line out to the
+     *  rook; an unpinned piece gets no restriction at all. */
+    static void testPinRay() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e4"), 4, WHITE)
+            .piece(sq("e7"), 5, BLACK).piece(sq("a8"), 6, BLACK)
+            .piece(sq("b2"), 3, WHITE)
+            .build();
+        long line = 0;
+        for (String s : new String[] {"e2", "e3", "e4", "e5", "e6", "e7"}) {
+            line |= 1L << sq(s);
+        }
+        assertEquals("pinned knight", line, Board.pinRay(b, sq("e4")));
+        assertEquals("free bishop", 0, Board.pinRay(b, sq("b2")));
+        assertEquals("no knight move survives", 0, Board.knightAttacks(sq("e4")) & Board.pinRay(b, sq("e4")));
+    }
+
+    /** Both sides start with the full set, and a capture takes one off */
+    static void testMaterial() {
+        long[] b = startPosition();
+        int[] full = {0, 0, 8, 2, 2, 2, 1, 1};
+        int[][] counts = Board.material(b);
+        for (int p = 2; p <= 7; p++) {
+            assertEquals("white " + p, full[p], counts[WHITE.index()][p]);
+            assertEquals("black " + p, full[p], counts[BLACK.index()][p]);
+        }
+        Board.setPiece(b, sq("e3"), 4, WHITE);
+        Board.removePiece(b, sq("g1"));
+        Board.setPiece(b, sq("d5"), 2, BLACK);
+        Board.removePiece(b, sq("d7"));
+        Move capture = find(Board.generateCaptures(b, WHITE), "e3d5");
+        counts = Board.material(Board.withMove(b, capture));
+        assertEquals("black pawns", 7, counts[BLACK.index()][2]);
+        assertEquals("white knights", 2, counts[WHITE.index()][4]);
+    }
+
+    /** a1 to h8 is 7 king steps and 14 rook steps, neighbours are 1 */
+    static void testDistances() {
+        assertEquals("chebyshev a1 h8", 7, Board.chebyshevDistance(sq("a1"), sq("h8")));
+        assertEquals("manhattan a1 h8", 14, Board.manhattanDistance(sq("a1"), sq("h8")));
+        assertEquals("chebyshev e4 f5", 1, Board.chebyshevDistance(sq("e4"), sq("f5")));
+        assertEquals("manhattan e4 e5", 1, Board.manhattanDistance(sq("e4"), sq("e5")));
+        assertEquals("manhattan e4 f5", 2, Board.manhattanDistance(sq("e4"), sq("f5")));
+        assertEquals("no wrap h1 a2", 7, Board.chebyshevDistance(sq("h1"), sq("a2")));
+        assertEquals("same square", 0, Board.chebyshevDistance(sq("d4"), sq("d4")));
+    }
+
+    /** The tables for h8, square 63, come out right despite the sign bit */
+    static void testTablesForTopSquare() {
+        assertEquals("north of h8", 0, Board.ray(63, 8));
+        assertEquals("south of h8", Defaults.file(7) & ~(1L << 63), Board.ray(63, -8));
+        assertEquals("west of h8", Defaults.rank(7) & ~(1L << 63), Board.ray(63, -1));
+        assertEquals("south west of h8", Defaults.DIAGONAL & ~(1L << 63), Board.ray(63, -9));
+        assertEquals("king on h8", (1L << sq("g8")) | (1L << sq("g7")) | (1L << sq("h7")),
+                     Board.kingAttacks(63));
+        assertEquals("knight on h8", (1L << sq("g6")) | (1L << sq("f7")), Board.knightAttacks(63));
+        assertEquals("north of h1", Defaults.file(7) & ~(1L << 7), Board.ray(7, 8));
+        assertEquals("north east of a1", Defaults.DIAGONAL & ~1L, Board.ray(0, 9));
+        assertEquals("south east of a8", Defaults.ANTI_DIAGONAL & ~(1L << 56), Board.ray(56, -7));
+    }
+
+    /** Overlaying the b1 knight's attacks on the start board marks exactly
+     *  a3, c3 and d2, and the flipped, labelled view agrees. */
+    static void testRenderOverlay() {
+        long[] b = startPosition();
+        String out = Board.render(b, Board.knightAttacks(sq("b1")));
+        String[] rows = out.split("\n");
+        assertEquals("eight rows", 8, rows.length);
+        assertEquals("rank 8", " r n b q k b n r", rows[0]);
+        assertEquals("rank 3", "*. .*. . . . . .", rows[5]);
+        assertEquals("rank 2", " P P P*P P P P P", rows[6]);
+        assertEquals("rank 1", " R N B Q K B N R", rows[7]);
+        assertEquals("three marks", 3, out.length() - out.replace("*", "").length());
+        String flipped = Board.render(b, Board.knightAttacks(sq("b1")), true, true);
+        String[] frows = flipped.split("\n");
+        assertEquals("flipped rank 1", "1 R N B K Q B N R", frows[0]);
+        assertEquals("flipped rank 3", "3 . . . . .*. .*.", frows[2]);
+        assertEquals("flipped files", "  h g f e d c b a", frows[8]);
+    }
+
+    /** With the black king en prise and white to move, black is reported
+     *  in check and no move capturing the king is ever handed out; with
+     *  assertions on the generator refuses the position outright. */
+    static void testNoKingCaptures() {
+        long[] b = new long[9];
+        Board.setPiece(b, sq("a1"), 6, WHITE);
+        Board.setPiece(b, sq("e8"), 6, BLACK);
+        Board.setPiece(b, sq("e4"), 5, WHITE);
+        Board.setPiece(b, sq("f7"), 4, WHITE);
+        Board.setPiece(b, sq("d7"), 2, BLACK);
+        assertTrue("black in check", Board.inCheck(b, BLACK));
+        assertEquals("checked by the rook", 1L << sq("e4"), Board.checkers(b, BLACK));
+        try {
+            for (Move m : Board.generateMoves(b, WHITE)) {
+                assertFalse(m + " takes the king", m.isCapture() && m.getCapture() == 6);
+            }
+            assertFalse("generator should have asserted", assertionsEnabled());
+        } catch (AssertionError e) {
+            assertTrue("only with assertions on", assertionsEnabled());
+            assertTrue(e.getMessage(), e.getMessage().startsWith("King capture generated"));
+        }
+    }
+
+    /** The start position fills all twelve planes with the right counts,
+     *  in absolute orientation, and they cover the board without overlap. */
+    static void testToPlanes() {
+        long[] b = startPosition();
+        long[] planes = Board.toPlanes(b);
+        int[] counts = {8, 2, 2, 2, 1, 1};
+        long union = 0;
+        for (int i = 0; i < 12; i++) {
+            assertEquals("plane " + i, counts[i % 6], Long.bitCount(planes[i]));
+            assertEquals("plane " + i + " overlaps", 0, union & planes[i]);
+            union |= planes[i];
+        }
+        assertEquals("covers the pieces", Board.occupancy(b), union);
+        assertEquals("white pawns", Defaults.rank(1), planes[0]);
+        assertEquals("black pawns", Defaults.rank(6), planes[6]);
+        assertEquals("black queen", 1L << sq("d8"), planes[11]);
+        Board.make(b, find(Board.generateMoves(b, WHITE), "e2e4"));
+        assertEquals("black's planes don't move with the turn", Defaults.rank(6), Board.toPlanes(b)[6]);
+    }
+
+    /** A lone rook check has one checker, a discovered double check two */
+    static void testCheckers() {
+        long[] single = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("e2"), 5, WHITE)
+            .sideToMove(BLACK)
+            .build();
+        assertEquals("rook", 1L << sq("e2"), Board.checkers(single, BLACK));
+        assertEquals("white isn't checked", 0, Board.checkers(single, WHITE));
+        long[] before = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("e1"), 5, WHITE).piece(sq("e4"), 4, WHITE)
+            .build();
+        long[] after = Board.withMove(before, find(Board.generateMoves(before, WHITE), "e4d6"));
+        long checkers = Board.checkers(after, BLACK);
+        assertEquals("double check", 2, Long.bitCount(checkers));
+        assertEquals("rook and knight", (1L << sq("e1")) | (1L << sq("d6")), checkers);
+    }
+
+    /** Flipped, the start position has black's pieces on the bottom rows,
+     *  and labels add rank numbers and a row of file letters. */
+    static void testRenderFlipAndLabels() {
+        long[] b = startPosition();
+        String[] plain = Board.render(b, 0, true, false).split("\n");
+        assertEquals("eight rows", 8, plain.length);
+        assertEquals("white on top", " R N B K Q B N R", plain[0]);
+        assertEquals("black pawns", " p p p p p p p p", plain[6]);
+        assertEquals("black on the bottom", " r n b k q b n r", plain[7]);
+        String[] labelled = Board.render(b, 0, false, true).split("\n");
+        assertEquals("nine rows", 9, labelled.length);
+        assertEquals("rank 8", "8 r n b q k b n r", labelled[0]);
+        assertEquals("rank 1", "1 R N B Q K B N R", labelled[7]);
+        assertEquals("files", "  a b c d e f g h", labelled[8]);
+        assertEquals("default is unflipped and unlabelled",
+                     Board.render(b, 0, false, false), Board.render(b, 0));
+    }
+
+    /** Returns every pseudo-legal move of P on B in coordinate notation,
+     *  worked out square by square without the generators. Kings keep
+     *  away from each other, as kingMoves does. */
+    private static Set<String> slowPseudoLegal(long[] b, Color p) {
+        int[][] straight = {{0, 1}, {1, 0}, {0, -1}, {-1, 0}};
+        int[][] diagonal = {{1, 1}, {1, -1}, {-1, -1}, {-1, 1}};
+        int[][] both = {{0, 1}, {1, 0}, {0, -1}, {-1, 0}, {1, 1}, {1, -1}, {-1, -1}, {-1, 1}};
+        long own = b[p.index()];
+        long enemy = b[p.opposite().index()];
+        long occ = own | enemy;
+        int enemyKing = Board.bitscanLSD(enemy & b[6]);
+        Set<String> moves = new TreeSet<String>();
+        for (int s = 0; s < 64; s++) {
+            if ((own & (1L << s)) == 0) {
+                continue;
+            }
+            long targets;
+            switch (Board.pieceAt(b, s)) {
+            case 2:
+                int ahead = s + 8 * p.sign();
+                int home = p == WHITE ? 1 : 6;
+                targets = Board.pawnAttacksFrom(s, p) & enemy;
+                if ((occ & (1L << ahead)) == 0) {
+                    targets |= 1L << ahead;
+                    int twice = ahead + 8 * p.sign();
+                    if (s / 8 == home && (occ & (1L << twice)) == 0) {
+                        targets |= 1L << twice;
+                    }
+                }
+                break;
+            case 3:
+                targets = slowSlide(b, s, p, diagonal);
+                break;
+            case 4:
+                targets = Board.knightAttacks(s) & ~own;
+                break;
+            case 5:
+                targets = slowSlide(b, s, p, straight);
+                break;
+            case 6:
+                targets = Board.kingAttacks(s) & ~own & ~Board.kingAttacks(enemyKing);
+                break;
+            default:
+                targets = slowSlide(b, s, p, both);
+                break;
+            }
+            for (int t = 0; t < 64; t++) {
+                if ((targets & (1L << t)) != 0) {
+                    moves.add(Move.squareName(s) + Move.squareName(t));
+                }
+            }
+        }
+        return moves;
+    }
+
+    /** Checks that the quiet moves and captures of P on B partition its
+     *  pseudo-legal moves: nothing in both, nothing missing, nothing extra. */
+    private static void assertPartition(long[] b, Color p) {
+        Set<String> seen = new TreeSet<String>();
+        for (Move m : Board.generateQuietMoves(b, p)) {
+            assertFalse(m + " quiet but a capture", m.isCapture());
+            assertEquals(m + " lands on a piece", null, Board.colorAt(b, m.getCoords()[1]));
+            assertTrue(m + " twice", seen.add(m.toString()));
+        }
+        for (Move m : Board.generateCaptures(b, p)) {
+            assertTrue(m + " marked a capture", m.isCapture());
+            assertEquals(m + " captures nothing", p.opposite(), Board.colorAt(b, m.getCoords()[1]));
+            assertEquals(m + " captured piece", Board.pieceAt(b, m.getCoords()[1]), m.getCapture());
+            assertTrue(m + " both quiet and capture", seen.add(m.toString()));
+        }
+        assertEquals(p + " moves in\n" + Board.render(b, 0), slowPseudoLegal(b, p), seen);
+    }
+
+    /** Quiet moves and captures split the pseudo-legal moves exactly, in a
+     *  busy tactical position and in random legal ones. */
+    static void testQuietAndCapturesPartitionMoves() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("d1"), 7, WHITE).piece(sq("h1"), 5, WHITE).piece(sq("a1"), 5, WHITE)
+            .piece(sq("c4"), 3, WHITE).piece(sq("f3"), 4, WHITE).piece(sq("e5"), 4, WHITE)
+            .piece(sq("d5"), 2, WHITE).piece(sq("g2"), 2, WHITE).piece(sq("c3"), 2, WHITE)
+            .piece(sq("f6"), 4, BLACK).piece(sq("c6"), 4, BLACK).piece(sq("b4"), 3, BLACK)
+            .piece(sq("e7"), 7, BLACK).piece(sq("a8"), 5, BLACK).piece(sq("h8"), 5, BLACK)
+            .piece(sq("e6"), 2, BLACK).piece(sq("f7"), 2, BLACK).piece(sq("a3"), 2, BLACK)
+            .build();
+        assertPartition(b, WHITE);
+        assertPartition(b, BLACK);
+        Random random = new Random(1401);
+        int checked = 0;
+        while (checked < 100) {
+            long[] r = randomBoard(random);
+            try {
+                Board.validate(r, WHITE);
+            } catch (IllegalArgumentException e) {
+                continue;
+            }
+            assertPartition(r, WHITE);
+            if (!Board.inCheck(r, WHITE)) {
+                assertPartition(r, BLACK);
+            }
+            checked += 1;
+        }
+    }
+
+    /** Fails with MSG unless tryMake refuses M on B with a message
+     *  containing WHY, leaving B as it was. */
+    private static void assertRefused(String msg, long[] b, Move m, String why) {
+        long[] before = b.clone();
+        try {
+            Board.tryMake(b, m);
+            fail(msg + " made");
+        } catch (IllegalArgumentException e) {
+            assertTrue(msg + ": " + e.getMessage(), e.getMessage().contains(why));
+        }
+        assertTrue(msg + " left the board alone", Arrays.equals(before, b));
+    }
+
+    /** Returns a move of PIECE by PLAYER from FROM to TO capturing CAPTURE */
+    private static Move capture(int from, int to, int piece, Color player, int capture) {
+        Move m = new Move(new int[] {from, to}, piece, player);
+        m.setCapture(capture);
+        return m;
+    }
+
+    /** tryMake says why it turns down a malformed move and doesn't touch the board */
+    static void testTryMakeRefusals() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e2"), 3, WHITE).piece(sq("a1"), 5, WHITE)
+            .piece(sq("h8"), 6, BLACK).piece(sq("e8"), 5, BLACK).piece(sq("d3"), 2, BLACK)
+            .build();
+        assertRefused("wrong piece", b, new Move(new int[] {sq("e2"), sq("d1")}, 4, WHITE), "No such piece");
+        assertRefused("wrong color", b, new Move(new int[] {sq("e8"), sq("e7")}, 5, WHITE), "No such piece");
+        assertRefused("empty capture", b, capture(sq("a1"), sq("a5"), 5, WHITE, 2), "Nothing to capture");
+        assertRefused("king capture", b, capture(sq("e8"), sq("e1"), 5, BLACK, 6), "Kings can't be captured");
+        assertRefused("occupied", b, new Move(new int[] {sq("e1"), sq("e2")}, 6, WHITE), "occupied");
+        assertRefused("unreachable", b, new Move(new int[] {sq("a1"), sq("b2")}, 5, WHITE), "can't reach");
+        assertRefused("pinned", b, capture(sq("e2"), sq("d3"), 3, WHITE, 2), "Leaves the king in check");
+        assertRefused("from 64", b, new Move(new int[] {64, 8}, 4, WHITE), "Square off the board");
+        assertRefused("to -1", b, new Move(new int[] {sq("a1"), -1}, 5, WHITE), "Square off the board");
+        Board.tryMake(b, new Move(new int[] {sq("a1"), sq("a7")}, 5, WHITE));
+        assertEquals("Ra7 made", 5, Board.pieceAt(b, sq("a7")));
+    }
+
+    /** setPiece and removePiece refuse squares off the board rather than
+     *  wrapping them round to the other end. */
+    static void testSetPieceRejectsOffBoardSquares() {
+        for (int square : new int[] {-1, 64, 100}) {
+            long[] b = new long[9];
+            try {
+                Board.setPiece(b, square, 5, WHITE);
+                fail("setPiece on " + square + " accepted");
+            } catch (IllegalArgumentException e) {
+                assertTrue("set nothing", Arrays.equals(new long[9], b));
+            }
+            b = startPosition();
+            try {
+                Board.removePiece(b, square);
+                fail("removePiece on " + square + " accepted");
+            } catch (IllegalArgumentException e) {
+                assertTrue("removed nothing", Arrays.equals(startPosition(), b));
+            }
+        }
+    }
+
+    /** pieceAt and colorAt refuse squares off the board instead of
+     *  reading the rook at the other end. */
+    static void testPieceAtRejectsOffBoardSquares() {
+        long[] b = startPosition();
+        for (int square : new int[] {-1, 64}) {
+            try {
+                Board.pieceAt(b, square);
+                fail("pieceAt " + square + " accepted");
+            } catch (IllegalArgumentException e) {
+                assertTrue(e.getMessage(), e.getMessage().contains("off the board"));
+            }
+            try {
+                Board.colorAt(b, square);
+                fail("colorAt " + square + " accepted");
+            } catch (IllegalArgumentException e) {
+                assertTrue(e.getMessage(), e.getMessage().contains("off the board"));
+            }
+        }
+    }
+
+    /** countLegalMoves agrees with filtering generateMoves through isLegal,
+     *  from the initial position, a pin, a check and random positions. */
+    static void testCountLegalMoves() {
+        long[] start = startPosition();
+        assertEquals("start", 20, Board.countLegalMoves(start, WHITE));
+        assertTrue("start restored", Arrays.equals(startPosition(), start));
+        long[] pinned = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e2"), 3, WHITE)
+            .piece(sq("e8"), 5, BLACK).piece(sq("a8"), 6, BLACK)
+            .build();
+        long[] checked = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("a2"), 5, WHITE)
+            .piece(sq("e8"), 6, BLACK).piece(sq("b5"), 3, WHITE)
+            .sideToMove(BLACK)
+            .build();
+        assertEquals("pinned bishop can't move", 4, Board.countLegalMoves(pinned, WHITE));
+        assertEquals("king in check", 4, Board.countLegalMoves(checked, BLACK));
+        Random random = new Random(1330);
+        for (int n = 0; n < 50; n++) {
+            long[] b = randomBoard(random);
+            for (Color p : Color.values()) {
+                if (Board.inCheck(b, p.opposite())) {
+                    continue;
+                }
+                int legal = 0;
+                for (Move m : Board.generateMoves(b, p)) {
+                    if (Board.isLegal(b, m)) {
+                        legal += 1;
+                    }
+                }
+                long[] before = b.clone();
+                assertEquals(p + " in\n" + Board.render(b, 0), legal, Board.countLegalMoves(b, p));
+                assertTrue("restored", Arrays.equals(before, b));
+            }
+        }
+    }
+
+    /** pieces lists the initial position's 32 pieces, agreeing square by
+     *  square with pieceAt and colorAt. */
+    static void testPiecesOfStartPosition() {
+        long[] b = startPosition();
+        List<Board.Placement> pieces = Board.pieces(b);
+        assertEquals("pieces", 32, pieces.size());
+        int[][] counts = new int[2][8];
+        int last = -1;
+        for (Board.Placement p : pieces) {
+            assertTrue("ascending squares", p.square() > last);
+            last = p.square();
+            assertEquals("piece on " + p.square(), Board.pieceAt(b, p.square()), p.piece());
+            assertEquals("color on " + p.square(), Board.colorAt(b, p.square()), p.color());
+            counts[p.color().index()][p.piece()] += 1;
+        }
+        assertTrue("counts", Arrays.deepEquals(Board.material(b), counts));
+        assertEquals("pawns", 8, counts[BLACK.index()][2]);
+        assertEquals("empty board", 0, Board.pieces(new long[9]).size());
+    }
+}
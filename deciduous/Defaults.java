@@ -1,22 +1,50 @@
 package deciduous;
 
+/** Named bitboard constants, laid out like the board representation:
+ *  bit 0 is a1, bit 7 is h1 and bit 63 is h8 (see Board.initBitBoard).
+ *  @author Andrew Berger */
 public class Defaults {
 
     /**Initial configuration of white */
-    final static long WHITE_PIECES = 18446462598732840960;
+    public final static long WHITE_PIECES = 0x000000000000FFFFL;
     /**Initial configuration of black */
-    final static long BLACK_PIECES = 65535;
+    public final static long BLACK_PIECES = 0xFFFF000000000000L;
     /** Initial configuration of pawns */
-    final static long PAWNS = 71776119061282560;
+    public final static long PAWNS = 0x00FF00000000FF00L;
     /** Initial configuration of bishops */
-    final static long BISHOPS = 2594073385365405732;
+    public final static long BISHOPS = 0x2400000000000024L;
     /** Initial confiuration of knights */
-    final static long KNIGHTS = 4755801206503243842;
+    public final static long KNIGHTS = 0x4200000000000042L;
     /** The initla location of rooks */
-    final static long ROOKS = 9295429630892703873;
+    public final static long ROOKS = 0x8100000000000081L;
     /** The initial location of the kings */
-    final static long KINGS = 576460752303423496;
-    /** The initial location of the queens */;
-    final static long QUEENS = 1152921504606846992;
+    public final static long KINGS = 0x1000000000000010L;
+    /** The initial location of the queens */
+    public final static long QUEENS = 0x0800000000000008L;
+
+    /** Every square of the a file */
+    public final static long A_FILE = 0x0101010101010101L;
+    /** Every square of the first rank */
+    public final static long FIRST_RANK = 0x00000000000000FFL;
+    /** The a1-h8 diagonal */
+    public final static long DIAGONAL = 0x8040201008040201L;
+    /** The h1-a8 anti-diagonal */
+    public final static long ANTI_DIAGONAL = 0x0102040810204080L;
+
+    /** Returns every square of FILE, 0-7, the a file being 0 */
+    public static long file(int file) {
+        if (file < 0 || file > 7) {
+            throw new IllegalArgumentException("No such file " + file);
+        }
+        return A_FILE << file;
+    }
+
+    /** Returns every square of RANK, 0-7, the first rank being 0 */
+    public static long rank(int rank) {
+        if (rank < 0 || rank > 7) {
+            throw new IllegalArgumentException("No such rank " + rank);
+        }
+        return FIRST_RANK << (rank * 8);
+    }
 
 }
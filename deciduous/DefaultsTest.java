@@ -0,0 +1,49 @@
+package deciduous;
+
+import static deciduous.UnitTest.*;
+
+/** Tests of the named bitboard constants.
+ *  @author Andrew Berger */
+class DefaultsTest {
+    /** The first file and rank are the named masks, the rest shift along,
+     *  and anything off the board is refused. */
+    static void testFilesAndRanks() {
+        assertEquals("file 0", Defaults.A_FILE, Defaults.file(0));
+        assertEquals("rank 0", Defaults.FIRST_RANK, Defaults.rank(0));
+        assertEquals("h file", 0x8080808080808080L, Defaults.file(7));
+        assertEquals("eighth rank", 0xFF00000000000000L, Defaults.rank(7));
+        long all = 0;
+        for (int i = 0; i < 8; i++) {
+            all |= Defaults.file(i);
+            assertEquals("one square in common", 1, Long.bitCount(Defaults.file(i) & Defaults.rank(i)));
+        }
+        assertEquals("files cover the board", -1L, all);
+        assertTrue("file 8", refuses(8, true));
+        assertTrue("file -1", refuses(-1, true));
+        assertTrue("rank 8", refuses(8, false));
+        assertTrue("rank -1", refuses(-1, false));
+    }
+
+    /** True iff Defaults.file, if FILE, else Defaults.rank, refuses INDEX */
+    private static boolean refuses(int index, boolean file) {
+        try {
+            if (file) {
+                Defaults.file(index);
+            } else {
+                Defaults.rank(index);
+            }
+            return false;
+        } catch (IllegalArgumentException e) {
+            return true;
+        }
+    }
+
+    /** The initial position constants don't overlap where they shouldn't */
+    static void testStartPositionConstants() {
+        assertEquals("colors disjoint", 0, Defaults.WHITE_PIECES & Defaults.BLACK_PIECES);
+        long pieces = Defaults.PAWNS | Defaults.BISHOPS | Defaults.KNIGHTS
+            | Defaults.ROOKS | Defaults.KINGS | Defaults.QUEENS;
+        assertEquals("pieces fill the colors", Defaults.WHITE_PIECES | Defaults.BLACK_PIECES, pieces);
+        assertEquals("32 pieces", 32, Long.bitCount(pieces));
+    }
+}
@@ -1,17 +1,227 @@
 package deciduous;
 
+import static deciduous.Color.*;
+
 /** Class holding our static evaluator and other fancy heuristics
  *  @author Andrew Berger */
 class Eval {
-    
-    /** Naive board evaluation */
-    public int staticEval(long[] board) {
-        //Fill me in
-        return 0;
+
+    /** The weights this evaluator scores with */
+    private final EvalConfig _config;
+    /** Pieces in the order recaptures are tried, cheapest first by _config */
+    private final int[] _cheapestFirst;
+
+    /** An evaluator using the default weights */
+    Eval() {
+        this(new EvalConfig());
+    }
+
+    /** An evaluator counting material with PIECEVALUES instead of the
+     *  defaults, indexed like the bitboard. The array is copied. */
+    Eval(int[] pieceValues) {
+        this(new EvalConfig().pieceValues(pieceValues));
+    }
+
+    /** An evaluator scoring with the weights of CONFIG, which is copied */
+    Eval(EvalConfig config) {
+        _config = new EvalConfig(config);
+        _cheapestFirst = new int[] {2, 3, 4, 5, 6, 7};
+        //insertion sort, stable so equal values keep the bitboard order
+        for (int i = 1; i < _cheapestFirst.length; i++) {
+            for (int j = i; j > 0 && pieceValue(_cheapestFirst[j]) < pieceValue(_cheapestFirst[j - 1]); j--) {
+                int swap = _cheapestFirst[j];
+                _cheapestFirst[j] = _cheapestFirst[j - 1];
+                _cheapestFirst[j - 1] = swap;
+            }
+        }
+    }
+
+    /** Material value of PIECE, 2-7, to this evaluator */
+    int pieceValue(int piece) {
+        return _config.pieceValue(piece);
+    }
+
+    /** Naive board evaluation from the point of view of PLAYER.
+     *  Material plus pawn structure, mobility, king safety and mop-up terms. */
+    public int staticEval(long[] board, Color player) {
+        int score = 0;
+        for (int p = 2; p <= 7; p++) {
+            score += pieceValue(p) * Long.bitCount(board[player.index()] & board[p]);
+            score -= pieceValue(p) * Long.bitCount(board[player.opposite().index()] & board[p]);
+        }
+        score += pawnStructure(board, player) - pawnStructure(board, player.opposite());
+        score += mobility(board, player) - mobility(board, player.opposite());
+        score += kingSafety(board, player) - kingSafety(board, player.opposite());
+        score += mopUp(board, player) - mopUp(board, player.opposite());
+        return score;
+    }
+
+    /** Endgame term for PLAYER once the opponent is down to a bare king.
+     *  Rewards pinning the enemy king against a corner, shrinking the
+     *  squares it can step to, and bringing PLAYER's own king up close. */
+    int mopUp(long[] board, Color player) {
+        Color other = player.opposite();
+        long theirs = board[other.index()];
+        if ((theirs & ~board[6]) != 0 || (board[player.index()] & ~board[6]) == 0) {
+            return 0;
+        }
+        int enemyKing = Board.bitscanLSD(theirs & board[6]);
+        int ownKing = Board.bitscanLSD(board[player.index()] & board[6]);
+        int file = enemyKing % 8;
+        int rank = enemyKing / 8;
+        int toCorner = Math.max(Math.min(file, 7 - file), Math.min(rank, 7 - rank));
+        int between = Board.chebyshevDistance(enemyKing, ownKing);
+        int freedom = 0;
+        long steps = Board.kingAttacks(enemyKing) & ~theirs;
+        while (steps != 0) {
+            int sq = Board.bitscanLSD(steps);
+            if (!Board.isAttacked(board, sq, player)) {
+                freedom += 1;
+            }
+            steps &= steps - 1;
+        }
+        return _config.cornerWeight() * (3 - toCorner) + _config.kingProximityWeight() * (7 - between)
+            + _config.kingFreedomWeight() * (8 - freedom);
+    }
+
+    /** Rewards the pawns sheltering PLAYER's king from the front and
+     *  penalizes every enemy attack on its king ring. */
+    int kingSafety(long[] board, Color player) {
+        long king = board[player.index()] & board[6];
+        if (king == 0) {
+            return 0;
+        }
+        int sq = Board.bitscanLSD(king);
+        long zone = Board.kingRing(board, player);
+        long shield = zone & ahead(sq / 8, player) & board[player.index()] & board[2];
+        int score = _config.pawnShieldBonus() * Long.bitCount(shield);
+        long enemies = board[player.opposite().index()];
+        while (zone != 0) {
+            int target = Board.bitscanLSD(zone);
+            score -= _config.kingZoneAttackPenalty() * Long.bitCount(Board.attackersTo(board, target) & enemies);
+            zone &= zone - 1;
+        }
+        return score;
+    }
+
+    /** Counts the squares PLAYER's knights, bishops, rooks and queens
+     *  attack, excluding those held by PLAYER's own pieces, each scaled
+     *  by their mobility weight. */
+    int mobility(long[] board, Color player) {
+        int score = 0;
+        for (int p = 3; p <= 7; p++) {
+            if (_config.mobilityWeight(p) == 0) {
+                continue;
+            }
+            long pieces = board[player.index()] & board[p];
+            while (pieces != 0) {
+                int sq = Board.bitscanLSD(pieces);
+                long attacks;
+                if (p == 3) {
+                    attacks = Board.bishopMoves(board, sq, player);
+                } else if (p == 4) {
+                    attacks = Board.knightMoves(board, sq, player);
+                } else if (p == 5) {
+                    attacks = Board.rookMoves(board, sq, player);
+                } else {
+                    attacks = Board.queenMoves(board, sq, player);
+                }
+                score += _config.mobilityWeight(p) * Long.bitCount(attacks);
+                pieces &= pieces - 1;
+            }
+        }
+        return score;
+    }
+
+    /** Scores the pawn skeleton of PLAYER: penalties for doubled and
+     *  isolated pawns, a bonus for each passed pawn. */
+    int pawnStructure(long[] board, Color player) {
+        long pawns = board[player.index()] & board[2];
+        long enemyPawns = board[player.opposite().index()] & board[2];
+        int score = 0;
+        for (int file = 0; file < 8; file++) {
+            int count = Long.bitCount(pawns & Defaults.file(file));
+            if (count > 1) {
+                score -= _config.doubledPawnPenalty() * (count - 1);
+            }
+            if (count > 0 && (pawns & adjacentFiles(file)) == 0) {
+                score -= _config.isolatedPawnPenalty() * count;
+            }
+        }
+        long rest = pawns;
+        while (rest != 0) {
+            int sq = Board.bitscanLSD(rest);
+            long span = (adjacentFiles(sq % 8) | Defaults.file(sq % 8)) & ahead(sq / 8, player);
+            if ((enemyPawns & span) == 0) {
+                score += _config.passedPawnBonus();
+            }
+            rest &= rest - 1;
+        }
+        return score;
+    }
+
+    /** Returns the files either side of FILE, 0-7 */
+    private static long adjacentFiles(int file) {
+        long result = 0;
+        if (file > 0) {
+            result |= Defaults.file(file - 1);
+        }
+        if (file < 7) {
+            result |= Defaults.file(file + 1);
+        }
+        return result;
+    }
+
+    /** Returns every rank strictly in front of RANK from PLAYER's side */
+    private static long ahead(int rank, Color player) {
+        if (player == WHITE) {
+            return rank == 7 ? 0 : -1L << ((rank + 1) * 8);
+        } else {
+            return (1L << (rank * 8)) - 1;
+        }
+    }
+
+    /** Static exchange evaluation of the capture M.
+     *  Returns the net material won by the side making M if both sides keep
+     *  recapturing on the destination square with their least valuable attacker.
+     *  Attackers are lifted off a copy of BOARD as they capture, so sliders
+     *  lined up behind them (x-rays) join the exchange. */
+    public int see(long[] board, Move m) {
+        long[] b = board.clone();
+        int square = m.getCoords()[1];
+        int[] gain = new int[32];
+        int depth = 0;
+        Color side = m.isWhite() ? WHITE : BLACK;
+        int piece = m.getPiece();
+        long from = 1L << m.getCoords()[0];
+        gain[0] = pieceValue(m.getCapture());
+        while (true) {
+            depth++;
+            gain[depth] = pieceValue(piece) - gain[depth - 1];
+            b[side.index()] ^= from;
+            b[piece] ^= from;
+            side = side.opposite();
+            long attackers = Board.attackersTo(b, square) & b[side.index()];
+            if (attackers == 0) {
+                break;
+            }
+            for (int p : _cheapestFirst) {
+                if ((attackers & b[p]) != 0) {
+                    piece = p;
+                    break;
+                }
+            }
+            from = 1L << Board.bitscanLSD(attackers & b[piece]);
+        }
+        while (--depth > 0) {
+            gain[depth - 1] = -Math.max(-gain[depth - 1], gain[depth]);
+        }
+        return gain[0];
     }
 
     public int fancyEval(long[] board) {
         //insert some machine learning 
+        return 0;
     }
 
 }
\ No newline at end of file
@@ -0,0 +1,186 @@
+package deciduous;
+
+/** The tunable weights of an Eval, all in centipawns.
+ *  Starts out holding the defaults below; change whichever you like
+ *  and hand it to new Eval(config), which takes a copy, e.g.
+ *  new Eval(new EvalConfig().pieceValue(7, 950).mobilityWeight(4, 6))
+ *  Pieces are indexed like the bitboard, 2-7.
+ *  @author Andrew Berger */
+class EvalConfig {
+
+    /** Material value of each piece, indexed like the bitboard */
+    private static final int[] PIECE_VALUES = {0, 0, 100, 330, 320, 500, 20000, 900};
+
+    /** Pawn structure weights */
+    private static final int DOUBLED_PAWN_PENALTY = 15;
+    private static final int ISOLATED_PAWN_PENALTY = 10;
+    private static final int PASSED_PAWN_BONUS = 20;
+
+    /** Centipawns per attacked square, indexed like the bitboard */
+    private static final int[] MOBILITY_WEIGHTS = {0, 0, 0, 3, 4, 2, 0, 1};
+
+    /** King safety weights */
+    private static final int PAWN_SHIELD_BONUS = 12;
+    private static final int KING_ZONE_ATTACK_PENALTY = 8;
+
+    /** Weights for driving a bare king into a corner */
+    private static final int CORNER_WEIGHT = 10;
+    private static final int KING_PROXIMITY_WEIGHT = 4;
+    private static final int KING_FREEDOM_WEIGHT = 6;
+
+    /** A config holding the default weights */
+    EvalConfig() {
+        _pieceValues = PIECE_VALUES.clone();
+        _mobilityWeights = MOBILITY_WEIGHTS.clone();
+        _doubledPawnPenalty = DOUBLED_PAWN_PENALTY;
+        _isolatedPawnPenalty = ISOLATED_PAWN_PENALTY;
+        _passedPawnBonus = PASSED_PAWN_BONUS;
+        _pawnShieldBonus = PAWN_SHIELD_BONUS;
+        _kingZoneAttackPenalty = KING_ZONE_ATTACK_PENALTY;
+        _cornerWeight = CORNER_WEIGHT;
+        _kingProximityWeight = KING_PROXIMITY_WEIGHT;
+        _kingFreedomWeight = KING_FREEDOM_WEIGHT;
+    }
+
+    /** A copy of CONFIG, which later changes to CONFIG don't reach */
+    EvalConfig(EvalConfig config) {
+        _pieceValues = config._pieceValues.clone();
+        _mobilityWeights = config._mobilityWeights.clone();
+        _doubledPawnPenalty = config._doubledPawnPenalty;
+        _isolatedPawnPenalty = config._isolatedPawnPenalty;
+        _passedPawnBonus = config._passedPawnBonus;
+        _pawnShieldBonus = config._pawnShieldBonus;
+        _kingZoneAttackPenalty = config._kingZoneAttackPenalty;
+        _cornerWeight = config._cornerWeight;
+        _kingProximityWeight = config._kingProximityWeight;
+        _kingFreedomWeight = config._kingFreedomWeight;
+    }
+
+    /** Material value of PIECE */
+    int pieceValue(int piece) {
+        return _pieceValues[checkPiece(piece)];
+    }
+
+    /** Sets the material value of PIECE to VALUE */
+    EvalConfig pieceValue(int piece, int value) {
+        _pieceValues[checkPiece(piece)] = value;
+        return this;
+    }
+
+    /** Sets every material value at once from VALUES, indexed like the
+     *  bitboard and so 8 long. The array is copied. */
+    EvalConfig pieceValues(int[] values) {
+        if (values.length != 8) {
+            throw new IllegalArgumentException("Piece values must be 8 long");
+        }
+        _pieceValues = values.clone();
+        return this;
+    }
+
+    /** Worth of each square PIECE attacks, see Eval.mobility */
+    int mobilityWeight(int piece) {
+        return _mobilityWeights[checkPiece(piece)];
+    }
+
+    /** Sets the worth of each square PIECE attacks to WEIGHT */
+    EvalConfig mobilityWeight(int piece, int weight) {
+        _mobilityWeights[checkPiece(piece)] = weight;
+        return this;
+    }
+
+    int doubledPawnPenalty() {
+        return _doubledPawnPenalty;
+    }
+
+    EvalConfig doubledPawnPenalty(int weight) {
+        _doubledPawnPenalty = weight;
+        return this;
+    }
+
+    int isolatedPawnPenalty() {
+        return _isolatedPawnPenalty;
+    }
+
+    EvalConfig isolatedPawnPenalty(int weight) {
+        _isolatedPawnPenalty = weight;
+        return this;
+    }
+
+    int passedPawnBonus() {
+        return _passedPawnBonus;
+    }
+
+    EvalConfig passedPawnBonus(int weight) {
+        _passedPawnBonus = weight;
+        return this;
+    }
+
+    int pawnShieldBonus() {
+        return _pawnShieldBonus;
+    }
+
+    EvalConfig pawnShieldBonus(int weight) {
+        _pawnShieldBonus = weight;
+        return this;
+    }
+
+    int kingZoneAttackPenalty() {
+        return _kingZoneAttackPenalty;
+    }
+
+    EvalConfig kingZoneAttackPenalty(int weight) {
+        _kingZoneAttackPenalty = weight;
+        return this;
+    }
+
+    int cornerWeight() {
+        return _cornerWeight;
+    }
+
+    EvalConfig cornerWeight(int weight) {
+        _cornerWeight = weight;
+        return this;
+    }
+
+    int kingProximityWeight() {
+        return _kingProximityWeight;
+    }
+
+    EvalConfig kingProximityWeight(int weight) {
+        _kingProximityWeight = weight;
+        return this;
+    }
+
+    int kingFreedomWeight() {
+        return _kingFreedomWeight;
+    }
+
+    EvalConfig kingFreedomWeight(int weight) {
+        _kingFreedomWeight = weight;
+        return this;
+    }
+
+    /** Returns PIECE, or throws IllegalArgumentException unless it is 2-7 */
+    private static int checkPiece(int piece) {
+        if (piece < 2 || piece > 7) {
+            throw new IllegalArgumentException("No such piece " + piece);
+        }
+        return piece;
+    }
+
+    /** Material values, indexed like the bitboard */
+    private int[] _pieceValues;
+    /** Mobility weights, indexed like the bitboard */
+    private int[] _mobilityWeights;
+    /** Pawn structure weights */
+    private int _doubledPawnPenalty;
+    private int _isolatedPawnPenalty;
+    private int _passedPawnBonus;
+    /** King safety weights */
+    private int _pawnShieldBonus;
+    private int _kingZoneAttackPenalty;
+    /** Mop-up weights */
+    private int _cornerWeight;
+    private int _kingProximityWeight;
+    private int _kingFreedomWeight;
+}
@@ -0,0 +1,147 @@
+package deciduous;
+
+import static deciduous.Color.*;
+import static deciduous.UnitTest.*;
+
+/** Tests of the static evaluator and static exchange evaluation.
+ *  @author Andrew Berger */
+class EvalTest {
+
+    /** An evaluator with the default weights */
+    private static final Eval EVAL = new Eval();
+
+    /** A queen taking a pawn that a pawn defends loses material */
+    static void testSeeDefendedPawnIsLosing() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("h8"), 6, BLACK)
+            .piece(sq("d1"), 7, WHITE).piece(sq("d5"), 2, BLACK)
+            .piece(sq("e6"), 2, BLACK)
+            .build();
+        Move m = find(Board.generateCaptures(b, WHITE), "d1d5");
+        assertTrue("Qxd5 generated", m != null);
+        assertEquals("see", 100 - 900, EVAL.see(b, m));
+    }
+
+    /** An undefended capture wins exactly the victim */
+    static void testSeeUndefendedWinsVictim() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("h8"), 6, BLACK)
+            .piece(sq("a1"), 5, WHITE).piece(sq("a5"), 4, BLACK)
+            .build();
+        Move m = find(Board.generateCaptures(b, WHITE), "a1a5");
+        assertTrue("Rxa5 generated", m != null);
+        assertEquals("see", new EvalConfig().pieceValue(4), EVAL.see(b, m));
+    }
+
+    /** A rook behind the capturing rook backs it up through the x-ray */
+    static void testSeeCountsXrays() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("h8"), 6, BLACK)
+            .piece(sq("a1"), 5, WHITE).piece(sq("a2"), 5, WHITE)
+            .piece(sq("a5"), 5, BLACK).piece(sq("a8"), 5, BLACK)
+            .build();
+        Move m = find(Board.generateCaptures(b, WHITE), "a2a5");
+        assertEquals("see", 500, EVAL.see(b, m));
+    }
+
+    /** Doubled pawns score below the same pawns split over two files,
+     *  and a lone pawn with nothing in front of it earns the passed bonus. */
+    static void testPawnStructure() {
+        long[] doubled = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("d2"), 2, WHITE).piece(sq("d3"), 2, WHITE)
+            .piece(sq("d7"), 2, BLACK).piece(sq("e7"), 2, BLACK)
+            .build();
+        long[] split = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("d2"), 2, WHITE).piece(sq("e3"), 2, WHITE)
+            .piece(sq("d7"), 2, BLACK).piece(sq("e7"), 2, BLACK)
+            .build();
+        assertTrue("doubled below split",
+                   EVAL.pawnStructure(doubled, WHITE) < EVAL.pawnStructure(split, WHITE));
+        long[] passed = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("a5"), 2, WHITE).piece(sq("h7"), 2, BLACK)
+            .build();
+        assertEquals("isolated but passed",
+                     new EvalConfig().passedPawnBonus() - new EvalConfig().isolatedPawnPenalty(),
+                     EVAL.pawnStructure(passed, WHITE));
+        assertTrue("passed pawn is a plus", EVAL.pawnStructure(passed, WHITE) > 0);
+    }
+
+    /** A queen in the middle of an open board is more mobile than one in a corner */
+    static void testCentralQueenMoreMobile() {
+        long[] central = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("b8"), 6, BLACK)
+            .piece(sq("d4"), 7, WHITE)
+            .build();
+        long[] cornered = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("b8"), 6, BLACK)
+            .piece(sq("h1"), 7, WHITE)
+            .build();
+        assertTrue("central above cornered",
+                   EVAL.mobility(central, WHITE) > EVAL.mobility(cornered, WHITE));
+        assertEquals("d4 queen", new EvalConfig().mobilityWeight(7) * 26, EVAL.mobility(central, WHITE));
+    }
+
+    /** Returns a position with WHITE castled short, shielded by PAWNS on
+     *  f2, g2 and h2 if asked, and a black queen on QUEEN if that is >= 0. */
+    private static long[] castled(boolean pawns, int queen) {
+        BoardBuilder builder = new BoardBuilder()
+            .piece(sq("g1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("a2"), 2, WHITE).piece(sq("a7"), 2, BLACK);
+        if (pawns) {
+            builder.piece(sq("f2"), 2, WHITE).piece(sq("g2"), 2, WHITE).piece(sq("h2"), 2, WHITE);
+        }
+        if (queen >= 0) {
+            builder.piece(queen, 7, BLACK);
+        }
+        return builder.build();
+    }
+
+    /** Stripping the pawns in front of a castled king costs king safety,
+     *  and an enemy queen bearing down on it costs more. */
+    static void testKingSafety() {
+        int shielded = EVAL.kingSafety(castled(true, -1), WHITE);
+        int bare = EVAL.kingSafety(castled(false, -1), WHITE);
+        int attacked = EVAL.kingSafety(castled(false, sq("h4")), WHITE);
+        assertTrue("shield counts", bare < shielded);
+        assertTrue("queen near the king counts", attacked < bare);
+    }
+
+    /** Raising the value of a piece in the config moves the evaluation of
+     *  a position a piece up by exactly the difference. */
+    static void testConfiguredPieceValues() {
+        long[] b = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("h8"), 6, BLACK)
+            .piece(sq("a2"), 5, WHITE)
+            .piece(sq("h7"), 2, BLACK)
+            .build();
+        EvalConfig config = new EvalConfig();
+        int before = new Eval(config).staticEval(b, WHITE);
+        config.pieceValue(5, config.pieceValue(5) + 50);
+        assertEquals("rook worth 50 more", before + 50, new Eval(config).staticEval(b, WHITE));
+        assertEquals("black sees it too", -(before + 50), new Eval(config).staticEval(b, BLACK));
+        config.pieceValue(5, 0);
+        Eval eval = new Eval(config);
+        config.pieceValue(5, 1000);
+        assertEquals("copied on construction", 0, eval.pieceValue(5));
+    }
+
+    /** The positional weights and SEE follow the config as well */
+    static void testConfiguredWeights() {
+        long[] b = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("b8"), 6, BLACK)
+            .piece(sq("d4"), 7, WHITE)
+            .build();
+        Eval heavy = new Eval(new EvalConfig().mobilityWeight(7, 10));
+        assertEquals("queen mobility", 10 * 26, heavy.mobility(b, WHITE));
+        long[] c = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("h8"), 6, BLACK)
+            .piece(sq("a1"), 5, WHITE).piece(sq("a5"), 4, BLACK)
+            .build();
+        Move m = find(Board.generateCaptures(c, WHITE), "a1a5");
+        Eval knights = new Eval(new EvalConfig().pieceValue(4, 275));
+        assertEquals("see with a cheap knight", 275, knights.see(c, m));
+    }
+}
@@ -0,0 +1,124 @@
+package deciduous;
+
+import java.util.ArrayList;
+
+import static deciduous.Color.*;
+
+/** A game in progress: the board, whose turn it is, and the positions
+ *  played so far. Unlike Board.make, play only takes legal moves and says
+ *  how the game stands afterwards, e.g.
+ *  Game g = new Game(); g.play(m); if (g.result() != Game.Result.ONGOING) ...
+ *  Promotions, castling, en passant and the fifty move rule aren't known
+ *  to the board yet, so they aren't known here either.
+ *  @author Andrew Berger */
+class Game {
+
+    /** How a game stands after a move */
+    enum Result {
+        ONGOING, WHITE_WINS, BLACK_WINS, STALEMATE, DRAW
+    }
+
+    /** A game from the initial position, WHITE to move */
+    Game() {
+        this(new long[] {
+            Defaults.WHITE_PIECES, Defaults.BLACK_PIECES, Defaults.PAWNS,
+            Defaults.BISHOPS, Defaults.KNIGHTS, Defaults.ROOKS,
+            Defaults.KINGS, Defaults.QUEENS, 0
+        }, WHITE);
+    }
+
+    /** A game from BOARD, which is copied, with TOMOVE to move.
+     *  Throws IllegalArgumentException if Board.validate rejects it. */
+    Game(long[] board, Color toMove) {
+        Board.validate(board, toMove);
+        _board = board.clone();
+        _toMove = toMove;
+        _history = new ArrayList<Long>();
+        _history.add(Board.hash(_board));
+        _result = judge();
+    }
+
+    /** Plays M, which must be a legal move of the side to move, and
+     *  returns the result of the game afterwards. An illegal M, or any
+     *  move once the game is over, throws IllegalArgumentException and
+     *  leaves the game as it was. */
+    Result play(Move m) {
+        if (_result != Result.ONGOING) {
+            throw new IllegalArgumentException("The game is over: " + _result);
+        }
+        if (m.getPlayer() != _toMove) {
+            throw new IllegalArgumentException("It is " + _toMove + "'s move");
+        }
+        Board.tryMake(_board, m);
+        if (m.getPiece() == 2 || m.isCapture()) {
+            _history.clear();
+        }
+        _history.add(Board.hash(_board));
+        _toMove = _toMove.opposite();
+        _result = judge();
+        return _result;
+    }
+
+    /** The result of the game so far */
+    Result result() {
+        return _result;
+    }
+
+    /** Who moves next */
+    Color toMove() {
+        return _toMove;
+    }
+
+    /** A copy of the current position */
+    long[] board() {
+        return _board.clone();
+    }
+
+    /** Returns the result of the current position with _toMove to move */
+    private Result judge() {
+        if (Board.countLegalMoves(_board, _toMove) == 0) {
+            if (!Board.inCheck(_board, _toMove)) {
+                return Result.STALEMATE;
+            }
+            return _toMove == WHITE ? Result.BLACK_WINS : Result.WHITE_WINS;
+        }
+        if (repetitions() >= 3 || insufficientMaterial()) {
+            return Result.DRAW;
+        }
+        return Result.ONGOING;
+    }
+
+    /** Returns how many times the current position has occurred since the
+     *  last pawn move or capture. The hash doesn't know whose turn it is,
+     *  so only every other entry is compared. */
+    private int repetitions() {
+        int top = _history.size() - 1;
+        long key = _history.get(top);
+        int seen = 0;
+        for (int i = top; i >= 0; i -= 2) {
+            if (_history.get(i) == key) {
+                seen += 1;
+            }
+        }
+        return seen;
+    }
+
+    /** True iff neither side can possibly mate: bare kings, or a lone
+     *  bishop or knight against a bare king. */
+    private boolean insufficientMaterial() {
+        long others = Board.occupancy(_board) & ~_board[6];
+        if (Long.bitCount(others) > 1) {
+            return false;
+        }
+        return (others & (_board[2] | _board[5] | _board[7])) == 0;
+    }
+
+    /** The position, played on in place */
+    private long[] _board;
+    /** Who moves next */
+    private Color _toMove;
+    /** Hashes of the positions since the last pawn move or capture, oldest first */
+    private ArrayList<Long> _history;
+    /** Result of the current position, see judge */
+    private Result _result;
+}
@@ -0,0 +1,84 @@
+package deciduous;
+
+import java.util.Arrays;
+
+import static deciduous.Color.*;
+import static deciduous.UnitTest.*;
+
+/** Tests of the legality checking game wrapper.
+ *  @author Andrew Berger */
+class GameTest {
+    /** Plays NAMES in turn on G, returning the result after the last */
+    private static Game.Result play(Game g, String... names) {
+        Game.Result result = null;
+        for (String name : names) {
+            Move m = find(Board.generateMoves(g.board(), g.toMove()), name);
+            assertTrue(name + " generated", m != null);
+            result = g.play(m);
+        }
+        return result;
+    }
+
+    /** Fails with MSG unless G turns down M and stays as it was */
+    private static void assertRefused(String msg, Game g, Move m) {
+        long[] before = g.board();
+        Color toMove = g.toMove();
+        try {
+            g.play(m);
+            fail(msg + " played");
+        } catch (IllegalArgumentException e) {
+            assertTrue(msg + " left the board alone", Arrays.equals(before, g.board()));
+            assertEquals(msg + " left the turn alone", toMove, g.toMove());
+        }
+    }
+
+    /** A move into check, a move out of turn and a move of a piece that
+     *  isn't there are turned down without touching the game. */
+    static void testIllegalMovesRefused() {
+        Game g = new Game();
+        assertRefused("black first", g, new Move(new int[] {sq("e7"), sq("e5")}, 2, BLACK));
+        assertRefused("no knight", g, new Move(new int[] {sq("c1"), sq("d3")}, 4, WHITE));
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e2"), 3, WHITE)
+            .piece(sq("e8"), 5, BLACK).piece(sq("a8"), 6, BLACK)
+            .build();
+        Game pinned = new Game(b, WHITE);
+        assertRefused("pinned bishop", pinned, new Move(new int[] {sq("e2"), sq("d3")}, 3, WHITE));
+        assertEquals("still going", Game.Result.ONGOING, play(pinned, "e1d1"));
+    }
+
+    /** Fool's mate ends the game in black's favour and nothing more can be played */
+    static void testFoolsMate() {
+        Game g = new Game();
+        assertEquals("before the mate", Game.Result.ONGOING, play(g, "f2f3", "e7e5", "g2g4"));
+        assertEquals("mate", Game.Result.BLACK_WINS, play(g, "d8h4"));
+        assertEquals("result kept", Game.Result.BLACK_WINS, g.result());
+        assertRefused("after the mate", g, new Move(new int[] {sq("a2"), sq("a3")}, 2, WHITE));
+    }
+
+    /** A king with nowhere to go and no other piece to move is stalemated */
+    static void testStalemate() {
+        long[] b = new BoardBuilder()
+            .piece(sq("h1"), 6, WHITE).piece(sq("c5"), 7, WHITE)
+            .piece(sq("a8"), 6, BLACK)
+            .build();
+        assertEquals("stalemate", Game.Result.STALEMATE, play(new Game(b, WHITE), "c5b6"));
+    }
+
+    /** The initial position coming round a third time is a draw */
+    static void testThreefoldRepetition() {
+        Game g = new Game();
+        assertEquals("twice", Game.Result.ONGOING, play(g, "g1f3", "g8f6", "f3g1", "f6g8"));
+        assertEquals("not yet", Game.Result.ONGOING, play(g, "g1f3", "g8f6", "f3g1"));
+        assertEquals("three times", Game.Result.DRAW, play(g, "f6g8"));
+    }
+
+    /** Taking the last rook leaves a lone knight, which can't mate */
+    static void testInsufficientMaterial() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("b1"), 4, WHITE)
+            .piece(sq("e8"), 6, BLACK).piece(sq("e2"), 5, BLACK)
+            .build();
+        assertEquals("king and knight", Game.Result.DRAW, play(new Game(b, WHITE), "e1e2"));
+    }
+}
@@ -32,12 +32,17 @@ class Move {
     }
     
     public boolean isWhite() {
-        if (player == WHITE) {
+        if (_player == WHITE) {
             return true;
         }
         return false;
     }
 
+    /** The player making this move */
+    public Color getPlayer() {
+        return _player;
+    }
+
     public boolean isCapture() {
         return _capture;
     }
@@ -46,9 +51,48 @@ class Move {
         return _captured;
     }
 
+    /** Coordinate notation, e.g. e2e4. Squares are absolute, a1 is 0. */
+    @Override
+    public String toString() {
+        return squareName(_move[0]) + squareName(_move[1]);
+    }
+
+    /** Packs the move into 12 bits: source square in the low six bits,
+     *  destination in the next six. Piece and capture are left to the board. */
+    public int toIndex() {
+        return _move[0] | (_move[1] << 6);
+    }
+
+    /** Inverse of toIndex, resolving piece, player and capture on BOARD.
+     *  Returns null if the source square of INDEX is empty, or if the
+     *  destination holds a friendly piece or a king. */
+    static Move fromIndex(int index, long[] board) {
+        int from = index & 63;
+        int to = (index >>> 6) & 63;
+        int piece = Board.pieceAt(board, from);
+        if (piece == 0) {
+            return null;
+        }
+        Color player = Board.colorAt(board, from);
+        Move m = new Move(new int[] {from, to}, piece, player);
+        int victim = Board.pieceAt(board, to);
+        if (victim != 0) {
+            if (Board.colorAt(board, to) != player.opposite() || victim == 6) {
+                return null;
+            }
+            m.setCapture(victim);
+        }
+        return m;
+    }
+
+    /** Algebraic name of SQUARE, 0-63, e.g. 28 is e4 */
+    static String squareName(int square) {
+        return "" + (char) ('a' + square % 8) + (char) ('1' + square / 8);
+    }
+
     private int[] _move;
     private int _piece;
-    private int _player;
+    private Color _player;
     private boolean _capture;
     /** Bitboard position of capture piece*/
     private int _captured;
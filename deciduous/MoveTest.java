@@ -0,0 +1,58 @@
+package deciduous;
+
+import static deciduous.Color.*;
+import static deciduous.UnitTest.*;
+
+/** Tests of the Move container and its encodings.
+ *  @author Andrew Berger */
+class MoveTest {
+    /** Moves print in coordinate notation with absolute squares for both
+     *  sides. There are no promotion or castling moves to print yet. */
+    static void testToString() {
+        assertEquals("e2e4", "e2e4", new Move(new int[] {sq("e2"), sq("e4")}, 2, WHITE).toString());
+        assertEquals("g8f6", "g8f6", new Move(new int[] {sq("g8"), sq("f6")}, 4, BLACK).toString());
+        assertEquals("a1h8", "a1h8", new Move(new int[] {0, 63}, 3, WHITE).toString());
+        assertEquals("square 28", "e4", Move.squareName(28));
+    }
+
+    /** Every generated move, quiet or capture, survives toIndex/fromIndex */
+    static void testIndexRoundTrip() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("d4"), 7, WHITE).piece(sq("b2"), 2, WHITE)
+            .piece(sq("d7"), 4, BLACK).piece(sq("g7"), 3, BLACK)
+            .piece(sq("a3"), 5, BLACK)
+            .build();
+        int captures = 0;
+        for (Color c : Color.values()) {
+            for (Move m : Board.generateMoves(b, c)) {
+                int index = m.toIndex();
+                assertTrue(m + " fits 12 bits", index >= 0 && index < 4096);
+                Move back = Move.fromIndex(index, b);
+                assertEquals("coordinates", m.toString(), back.toString());
+                assertEquals(m + " piece", m.getPiece(), back.getPiece());
+                assertEquals(m + " player", m.getPlayer(), back.getPlayer());
+                assertEquals(m + " capture", m.isCapture(), back.isCapture());
+                if (m.isCapture()) {
+                    captures += 1;
+                    assertEquals(m + " captured", m.getCapture(), back.getCapture());
+                }
+            }
+        }
+        assertTrue("some captures", captures > 0);
+    }
+
+    /** fromIndex refuses an empty source, a friendly piece on the
+     *  destination and a king on it */
+    static void testFromIndexRejectsNonsense() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("e2"), 5, WHITE).piece(sq("d2"), 2, WHITE)
+            .sideToMove(BLACK)
+            .build();
+        assertEquals("empty source", null, Move.fromIndex(sq("e4") | (sq("e5") << 6), b));
+        assertEquals("own pawn", null, Move.fromIndex(sq("e2") | (sq("d2") << 6), b));
+        assertEquals("king", null, Move.fromIndex(sq("e2") | (sq("e8") << 6), b));
+        assertTrue("quiet", Move.fromIndex(sq("e2") | (sq("e5") << 6), b) != null);
+    }
+}
@@ -3,49 +3,229 @@ package deciduous;
 import java.util.HashMap;
 import java.util.Map;
 import java.util.ArrayList;
+import java.util.Collections;
+import java.util.Comparator;
+import java.util.List;
 
 import static deciduous.Color.*;
 
 class Search {
 
     Search() {
-        _board = new Board();
+        this(new Eval());
     }
 
-    private int negamax(int alpha, int beta, int depth, Color p, long[] b) {
+    /** A search scoring its leaves with EVAL, e.g. one with tuned piece values */
+    Search(Eval eval) {
+        this(eval, DEFAULT_DEPTH);
+    }
+
+    /** A search scoring its leaves with EVAL that looks DEPTH plies ahead,
+     *  DEPTH between 1 and MAX_DEPTH - 1. Captures are ordered by EVAL's
+     *  piece values too. */
+    Search(Eval eval, int depth) {
+        checkDepth(depth);
+        _eval = eval;
+        _depth = depth;
+        _orderRank = new int[8];
+        for (int p = 2; p <= 7; p++) {
+            for (int q = 2; q <= 7; q++) {
+                if (eval.pieceValue(q) < eval.pieceValue(p)) {
+                    _orderRank[p] += 1;
+                }
+            }
+        }
+        _history = new ArrayList<Long>();
+        _resets = new ArrayList<Integer>();
+        _killers = new Move[MAX_DEPTH][2];
+        _historyScores = new int[8][64];
+    }
+
+    /** Returns the move P should play on B, searching as deep as this
+     *  search was configured to. See bestMove(B, P, DEPTH) */
+    Move bestMove(long[] b, Color p) {
+        return bestMove(b, p, _depth);
+    }
+
+    /** Returns the move P should play on B according to a DEPTH ply
+     *  search, DEPTH between 1 and MAX_DEPTH - 1, or null if P has no
+     *  legal move. B is left as it was found. */
+    Move bestMove(long[] b, Color p, int depth) {
+        checkDepth(depth);
+        //lines coming back to B repeat it, so B is on the history while searching
+        boolean root = _history.isEmpty() || _history.get(_history.size() - 1) != Board.hash(b);
+        if (root) {
+            pushHistory(b);
+        }
+        Move best = null;
+        int alpha = LOW_SCORE;
+        List<Move> moves = Board.generateMoves(b, p);
+        orderMoves(moves, depth);
+        for (Move move : moves) {
+            b = Board.make(b, move);
+            if (Board.inCheck(b, p)) {
+                b = Board.unmake(b, move);
+                continue;
+            }
+            pushHistory(b, move);
+            int score = isRepetition(2) ? DRAW_SCORE : -negamax(-HIGH_SCORE, -alpha, depth - 1, p.opposite(), b);
+            popHistory();
+            b = Board.unmake(b, move);
+            if (best == null || score > alpha) {
+                best = move;
+                alpha = score;
+            }
+        }
+        if (root) {
+            popHistory();
+        }
+        return best;
+    }
+
+    int negamax(int alpha, int beta, int depth, Color p, long[] b) {
+        _nodes += 1;
         if (depth == 0) {
             return quiesce(alpha, beta, p, b);
         }
         int max = LOW_SCORE;
-        ArrayList<int[]> moves = _board.generateMoves(b);
-        for (int move[] : moves) {
-            b = make(b, move);
-            int prosp = -negamax(-beta, -alpha, depth - 1, p.opposite(), b);
-            b = unmake(b, move);
+        List<Move> moves = Board.generateMoves(b, p);
+        orderMoves(moves, depth);
+        boolean moved = false;
+        for (Move move : moves) {
+            b = Board.make(b, move);
+            //the generators are pseudo-legal, so moves into check are dropped here
+            if (Board.inCheck(b, p)) {
+                b = Board.unmake(b, move);
+                continue;
+            }
+            moved = true;
+            pushHistory(b, move);
+            //a position already on the path can be repeated into a draw
+            int prosp = isRepetition(2) ? DRAW_SCORE : -negamax(-beta, -alpha, depth - 1, p.opposite(), b);
+            popHistory();
+            b = Board.unmake(b, move);
             max = Math.max(max, prosp);
             alpha = Math.max(alpha, prosp);
             if (alpha >= beta) {
+                if (!move.isCapture()) {
+                    recordCutoff(move, depth);
+                }
                 break;
             }
         }
+        if (!moved) {
+            max = Board.inCheck(b, p) ? LOW_SCORE : DRAW_SCORE;
+        }
         addToTable(b, max, depth);
         return max;
     }
 
+    /** Turns the killer and history heuristics off, leaving quiet moves
+     *  in generation order behind the MVV-LVA sorted captures. Returns this
+     *  search; mostly useful for measuring what the heuristics buy. */
+    Search withoutQuietOrdering() {
+        _quietOrdering = false;
+        return this;
+    }
+
+    /** Number of positions negamax and quiesce have visited so far */
+    long nodes() {
+        return _nodes;
+    }
+
+    /** Sorts MOVES in place so that captures are tried first, most valuable
+     *  victim / least valuable attacker first, followed by the quiet moves. */
+    void orderMoves(List<Move> moves) {
+        Collections.sort(moves, new Comparator<Move>() {
+            public int compare(Move a, Move b) {
+                return mvvLva(b) - mvvLva(a);
+            }
+        });
+    }
+
+    /** Like orderMoves(MOVES), but quiet moves follow the captures with
+     *  this DEPTH's killers first and then by their history score. */
+    void orderMoves(List<Move> moves, final int depth) {
+        Collections.sort(moves, new Comparator<Move>() {
+            public int compare(Move a, Move b) {
+                return Integer.compare(orderKey(b, depth), orderKey(a, depth));
+            }
+        });
+    }
+
+    /** Ordering key of M at DEPTH, see orderMoves(MOVES, DEPTH) */
+    private int orderKey(Move m, int depth) {
+        if (m.isCapture()) {
+            return CAPTURE_KEY + mvvLva(m);
+        } else if (!_quietOrdering) {
+            return 0;
+        } else if (sameMove(m, _killers[depth][0])) {
+            return KILLER_KEY + 1;
+        } else if (sameMove(m, _killers[depth][1])) {
+            return KILLER_KEY;
+        }
+        return _historyScores[m.getPiece()][m.getCoords()[1]];
+    }
+
+    /** Remembers the quiet move M as a killer at DEPTH and bumps its
+     *  history score, since it just caused a beta cutoff. */
+    private void recordCutoff(Move m, int depth) {
+        if (!sameMove(m, _killers[depth][0])) {
+            _killers[depth][1] = _killers[depth][0];
+            _killers[depth][0] = m;
+        }
+        int to = m.getCoords()[1];
+        _historyScores[m.getPiece()][to] += depth * depth;
+        if (_historyScores[m.getPiece()][to] >= KILLER_KEY) {
+            for (int[] row : _historyScores) {
+                for (int sq = 0; sq < 64; sq++) {
+                    row[sq] /= 2;
+                }
+            }
+        }
+    }
+
+    /** True iff A and B move the same piece between the same squares */
+    private static boolean sameMove(Move a, Move b) {
+        return a != null && b != null && a.getPiece() == b.getPiece()
+            && a.getCoords()[0] == b.getCoords()[0] && a.getCoords()[1] == b.getCoords()[1];
+    }
+
+    /** Ordering key of M. Zero for quiet moves, otherwise positive and
+     *  dominated by the victim with the attacker breaking ties, pieces
+     *  ranked by the piece values of _eval. */
+    int mvvLva(Move m) {
+        if (!m.isCapture()) {
+            return 0;
+        }
+        return 8 * _orderRank[m.getCapture()] - _orderRank[m.getPiece()] + 8;
+    }
+
     /** Checks to make sure the opponent doesn't have a devastating reply
-     *  Worth some serious optimizations since most of the nodes will be spent here.*/
-    private int queisce(int alpha, int beta, Color p, long[] b) {
-        int curr_eval = _eval.staticEval(b);
+     *  Worth some serious optimizations since most of the nodes will be spent here.
+     *  Stands pat on the static eval and then only tries captures,
+     *  skipping the ones that lose material by static exchange.*/
+    int quiesce(int alpha, int beta, Color p, long[] b) {
+        _nodes += 1;
+        int curr_eval = _eval.staticEval(b, p);
         if (curr_eval >= beta) {
             return beta;
         } else if (alpha < curr_eval) {
             alpha = curr_eval;
         }
-        ArrayList<int[]> captures = _board.generateCaptures(b);
-        for (int[] capture : captures) {
-            b = make(b, capture);
-            int score = -queisce(-beta, -alpha);
-            b = unmake(b, capture);
+        List<Move> captures = Board.generateCaptures(b, p);
+        orderMoves(captures);
+        for (Move capture : captures) {
+            if (_eval.see(b, capture) < 0) {
+                continue;
+            }
+            b = Board.make(b, capture);
+            if (Board.inCheck(b, p)) {
+                b = Board.unmake(b, capture);
+                continue;
+            }
+            int score = -quiesce(-beta, -alpha, p.opposite(), b);
+            b = Board.unmake(b, capture);
             if (score >= beta) {
                 return score;
             } else if (score > alpha) {
@@ -55,20 +235,94 @@ class Search {
         return alpha;
     }
 
+    /** Records B, the position reached by playing M, on the history stack.
+     *  Pawn moves and captures can't be undone, so they open a new window. */
+    void pushHistory(long[] b, Move m) {
+        if (m.getPiece() == 2 || m.isCapture()) {
+            _resets.add(_history.size());
+        }
+        _history.add(Board.hash(b));
+    }
+
+    /** Records B on the history stack without a move leading to it, as
+     *  for the position a game started from. */
+    void pushHistory(long[] b) {
+        _history.add(Board.hash(b));
+    }
+
+    /** Inverse of either pushHistory. */
+    void popHistory() {
+        _history.remove(_history.size() - 1);
+        int last = _resets.size() - 1;
+        if (last >= 0 && _resets.get(last) == _history.size()) {
+            _resets.remove(last);
+        }
+    }
+
+    /** True iff the current position has occurred at least COUNT times
+     *  since the last pawn move or capture. The hash doesn't know whose
+     *  turn it is, so only every other entry is compared. */
+    boolean isRepetition(int count) {
+        int top = _history.size() - 1;
+        if (top < 0) {
+            return false;
+        }
+        int start = _resets.isEmpty() ? 0 : _resets.get(_resets.size() - 1);
+        long key = _history.get(top);
+        int seen = 0;
+        for (int i = top; i >= start; i -= 2) {
+            if (_history.get(i) == key) {
+                seen += 1;
+            }
+        }
+        return seen >= count;
+    }
+
+    /** Rejects a DEPTH the killer table has no room for */
+    private static void checkDepth(int depth) {
+        if (depth < 1 || depth >= MAX_DEPTH) {
+            throw new IllegalArgumentException("Search depth must be 1 to " + (MAX_DEPTH - 1));
+        }
+    }
+
     /** Adds board state to the hash table.
      *  Only updates if DEPTH > the current depth*/
     private void addToTable(long[] b, int score, int depth) {
 
     }
 
-    /** The instance of boardMethods. */
-    private Board _board;
     /** The instace of eval methods */
     private Eval _eval;
     /** Large hashMap holding all of our previously visited board states
      *  Key is the board hash
      *  Value is yet to be determined but will be at least depth, score and the move we picked last time*/
     private Map<Integer, int[]> _table;
+    /** Hashes of the positions on the current line, oldest first */
+    private ArrayList<Long> _history;
+    /** Indices into _history where a pawn move or capture started a new window */
+    private ArrayList<Integer> _resets;
+    /** Two killer moves for each remaining depth, most recent first */
+    private Move[][] _killers;
+    /** Cutoff history of quiet moves, indexed by piece then destination square */
+    private int[][] _historyScores;
+    /** False to order quiet moves without killers and history */
+    private boolean _quietOrdering = true;
+    /** Positions visited, see nodes */
+    private long _nodes;
+    /** Deepest search the killer table has room for */
+    private static final int MAX_DEPTH = 64;
+    /** Ordering keys above any history score; captures go above killers */
+    private static final int KILLER_KEY = 1 << 20;
+    private static final int CAPTURE_KEY = 1 << 21;
+    /** Relative worth of each piece for move ordering, indexed like the
+     *  bitboard: how many kinds of piece _eval values below it. */
+    private int[] _orderRank;
+    /** How many plies bestMove(B, P) looks ahead */
+    private int _depth;
+    /** Search depth unless one is asked for */
+    private static final int DEFAULT_DEPTH = 4;
+    /** Score of a drawn position, from either side */
+    private static final int DRAW_SCORE = 0;
     /** For min max purposes. Be careful of mod arith. */
     private int HIGH_SCORE = 1000000000;
     /** For min max purposes. Be careful of mod arith. */
@@ -0,0 +1,266 @@
+package deciduous;
+
+import java.util.Arrays;
+import java.util.List;
+
+import static deciduous.Color.*;
+import static deciduous.UnitTest.*;
+
+/** Tests of move ordering and the search itself.
+ *  @author Andrew Berger */
+class SearchTest {
+
+    /** A pawn taking a queen is tried before a queen taking a pawn */
+    static void testMvvLvaOrdersPawnTakesQueenFirst() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e8"), 6, BLACK)
+            .piece(sq("d4"), 2, WHITE).piece(sq("e5"), 7, BLACK)
+            .piece(sq("a1"), 7, WHITE).piece(sq("a6"), 2, BLACK)
+            .build();
+        List<Move> moves = Board.generateMoves(b, WHITE);
+        new Search().orderMoves(moves);
+        assertEquals("first move", "d4e5", moves.get(0).toString());
+        assertEquals("second move", "a1a6", moves.get(1).toString());
+    }
+
+    /** A shallow search from the initial position terminates */
+    static void testShallowSearchFromStart() {
+        long[] b = startPosition();
+        int score = new Search().negamax(-1000000000, 1000000000, 3, WHITE, b);
+        assertTrue("score is finite", Math.abs(score) < 1000000000);
+        assertTrue("board restored", Arrays.equals(startPosition(), b));
+    }
+
+    /** A queen grabbing a defended pawn at the horizon doesn't look like
+     *  it wins a pawn; quiescence stands pat on the static eval instead. */
+    static void testQuiesceSeesTheRecapture() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("h8"), 6, BLACK)
+            .piece(sq("d1"), 7, WHITE).piece(sq("d5"), 2, BLACK)
+            .piece(sq("e6"), 2, BLACK)
+            .build();
+        Eval eval = new Eval();
+        int standPat = eval.staticEval(b, WHITE);
+        int score = new Search(eval).quiesce(-1000000000, 1000000000, WHITE, b);
+        assertEquals("quiescence score", standPat, score);
+    }
+
+    /** Plays NAME, which must be a legal move of P on B, onto the history
+     *  of S as well as the board. */
+    private static void play(Search s, long[] b, Color p, String name) {
+        Move m = find(Board.generateMoves(b, p), name);
+        assertTrue(name + " generated", m != null);
+        Board.make(b, m);
+        s.pushHistory(b, m);
+    }
+
+    /** Shuffling the knights out and back three times is a threefold repetition */
+    static void testKnightShuffleRepeats() {
+        Search s = new Search();
+        long[] b = startPosition();
+        for (int i = 0; i < 3; i++) {
+            assertFalse("not yet threefold", s.isRepetition(3));
+            play(s, b, WHITE, "g1f3");
+            play(s, b, BLACK, "g8f6");
+            play(s, b, WHITE, "f3g1");
+            play(s, b, BLACK, "f6g8");
+        }
+        assertTrue("threefold", s.isRepetition(3));
+    }
+
+    /** A pawn move closes the window positions are counted in, and
+     *  taking it back reopens the old one. */
+    static void testPawnMoveResetsRepetition() {
+        Search s = new Search();
+        long[] b = startPosition();
+        for (int i = 0; i < 2; i++) {
+            play(s, b, WHITE, "g1f3");
+            play(s, b, BLACK, "g8f6");
+            play(s, b, WHITE, "f3g1");
+            play(s, b, BLACK, "f6g8");
+        }
+        assertTrue("start position twice", s.isRepetition(2));
+        play(s, b, WHITE, "e2e3");
+        assertFalse("pawn move is new", s.isRepetition(2));
+        play(s, b, BLACK, "e7e6");
+        play(s, b, WHITE, "g1f3");
+        play(s, b, BLACK, "g8f6");
+        play(s, b, WHITE, "f3g1");
+        play(s, b, BLACK, "f6g8");
+        assertTrue("repeated since the pawn move", s.isRepetition(2));
+        assertFalse("only twice since the pawn move", s.isRepetition(3));
+        for (int i = 0; i < 6; i++) {
+            s.popHistory();
+        }
+        assertTrue("window reopened", s.isRepetition(2));
+    }
+
+    /** Returns how many legal moves the king of P has on B */
+    private static int kingMoveCount(long[] b, Color p) {
+        int count = 0;
+        for (Move m : Board.generateMoves(b, p)) {
+            if (m.getPiece() == 6 && Board.isLegal(b, m)) {
+                count += 1;
+            }
+        }
+        return count;
+    }
+
+    /** With king and rook against a bare king, the search hems the
+     *  enemy king in rather than leaving it room. */
+    static void testMopUpShrinksTheBareKing() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e2"), 6, WHITE).piece(sq("h1"), 5, WHITE)
+            .piece(sq("e5"), 6, BLACK)
+            .build();
+        int before = kingMoveCount(b, BLACK);
+        Move best = new Search().bestMove(b, WHITE, 3);
+        assertTrue("found a move", best != null);
+        int after = kingMoveCount(Board.withMove(b, best), BLACK);
+        assertTrue(best + " leaves the king " + after + " of " + before + " moves", after < before);
+    }
+
+    /** Captures are ordered by the search's own piece values: valuing
+     *  knights above rooks puts the knight capture first. */
+    static void testMvvLvaFollowsPieceValues() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("h7"), 6, BLACK)
+            .piece(sq("a1"), 7, WHITE).piece(sq("a5"), 5, BLACK)
+            .piece(sq("d1"), 7, WHITE).piece(sq("d5"), 4, BLACK)
+            .build();
+        List<Move> moves = Board.generateCaptures(b, WHITE);
+        new Search().orderMoves(moves);
+        assertEquals("rook first by default", "a1a5", moves.get(0).toString());
+        Eval eval = new Eval(new EvalConfig().pieceValue(4, 600));
+        new Search(eval).orderMoves(moves);
+        assertEquals("knight first", "d1d5", moves.get(0).toString());
+    }
+
+    /** The depth is set per search and checked */
+    static void testConfiguredDepth() {
+        Move m = new Search(new Eval(), 1).bestMove(startPosition(), WHITE);
+        assertTrue("a move at depth 1", m != null);
+        try {
+            new Search(new Eval(), 0);
+            fail("depth 0 accepted");
+        } catch (IllegalArgumentException e) {
+            return;
+        }
+    }
+
+    /** From the initial position every refutation is a quiet move, so the
+     *  killers and history cut the tree down well below plain MVV-LVA. */
+    static void testQuietOrderingSearchesFewerNodes() {
+        Search heuristics = new Search(new Eval(), 4);
+        Search plain = new Search(new Eval(), 4).withoutQuietOrdering();
+        heuristics.bestMove(startPosition(), WHITE);
+        plain.bestMove(startPosition(), WHITE);
+        assertTrue(heuristics.nodes() + " nodes against " + plain.nodes(),
+                   heuristics.nodes() < plain.nodes());
+    }
+
+    /** The generators are pseudo-legal, but the search never plays a move
+     *  that leaves its own king in check, however much it seems to win. */
+    static void testSearchSkipsIllegalMoves() {
+        long[] b = new BoardBuilder()
+            .piece(sq("e1"), 6, WHITE).piece(sq("e2"), 3, WHITE)
+            .piece(sq("e8"), 5, BLACK).piece(sq("a8"), 6, BLACK)
+            .piece(sq("d3"), 7, BLACK)
+            .build();
+        assertTrue("Bxd3 generated", find(Board.generateCaptures(b, WHITE), "e2d3") != null);
+        for (int depth = 1; depth <= 3; depth++) {
+            Move m = new Search().bestMove(b, WHITE, depth);
+            assertTrue("a move at depth " + depth, m != null);
+            assertTrue(m + " at depth " + depth, Board.isLegal(b, m));
+        }
+    }
+
+    /** A side with no legal move scores as mated in check and as a draw out of it */
+    static void testNoLegalMoves() {
+        long[] stalemate = new BoardBuilder()
+            .piece(sq("h1"), 6, WHITE).piece(sq("b6"), 7, WHITE)
+            .piece(sq("a8"), 6, BLACK)
+            .sideToMove(BLACK)
+            .build();
+        assertEquals("stalemate", 0, new Search().negamax(-1000000000, 1000000000, 2, BLACK, stalemate));
+        assertEquals("no best move", null, new Search().bestMove(stalemate, BLACK, 2));
+        long[] mate = new BoardBuilder()
+            .piece(sq("h1"), 6, WHITE).piece(sq("b7"), 7, WHITE).piece(sq("a5"), 4, WHITE)
+            .piece(sq("a8"), 6, BLACK)
+            .sideToMove(BLACK)
+            .build();
+        assertEquals("mate", -1000000000, new Search().negamax(-1000000000, 1000000000, 2, BLACK, mate));
+    }
+
+    /** Returns S after recording on it the line that leads up to B, a
+     *  position with the white queen on d2 and black's knight on g8: the
+     *  game starts from the same position with the queen on d1 and goes
+     *  Ng8-f6, Qd1-d2, Nf6-g8. B itself is the last entry, white to move. */
+    private static Search shuffled(Search s, long[] b) {
+        long[] start = b.clone();
+        Board.removePiece(start, sq("d2"));
+        Board.setPiece(start, sq("d1"), 7, WHITE);
+        s.pushHistory(start);
+        play(s, start, BLACK, "g8f6");
+        play(s, start, WHITE, "d1d2");
+        play(s, start, BLACK, "f6g8");
+        assertTrue("history ends in B", Arrays.equals(start, b));
+        return s;
+    }
+
+    /** White, a queen up, won't play back into a repetition, while black
+     *  in the same spot a move later heads straight for it. */
+    static void testRepetitionsAreDraws() {
+        long[] b = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("d2"), 7, WHITE)
+            .piece(sq("h8"), 6, BLACK).piece(sq("g8"), 4, BLACK)
+            .build();
+        Move back = find(Board.generateMoves(b, WHITE), "d2d1");
+        long[] repeated = Board.withMove(b, back);
+        assertTrue("would be winning without history",
+                   new Search().negamax(-1000000000, 1000000000, 1, BLACK, repeated) < -500);
+        Search winning = shuffled(new Search(), b);
+        Move white = winning.bestMove(b, WHITE, 2);
+        assertFalse("white avoids " + white, "d2d1".equals(white.toString()));
+
+        Search losing = shuffled(new Search(), b);
+        long[] c = b.clone();
+        play(losing, c, WHITE, "d2d1");
+        play(losing, c, BLACK, "g8f6");
+        play(losing, c, WHITE, "d1d2");
+        assertEquals("black repeats", "f6g8", losing.bestMove(c, BLACK, 2).toString());
+    }
+
+    /** bestMove checks the depth it is handed just as the constructor does */
+    static void testBestMoveRejectsBadDepths() {
+        for (int depth : new int[] {0, -1, 64}) {
+            long[] b = startPosition();
+            try {
+                new Search().bestMove(b, WHITE, depth);
+                fail("depth " + depth + " accepted");
+            } catch (IllegalArgumentException e) {
+                assertTrue("board untouched", Arrays.equals(startPosition(), b));
+            }
+        }
+    }
+
+    /** bestMove puts the position it is asked about on the history itself,
+     *  so a line through it counts as repeating it even when the caller
+     *  hasn't recorded it. Black, a queen down, heads back to h6, rim
+     *  and all. */
+    static void testBestMoveRecordsTheRoot() {
+        long[] b = new BoardBuilder()
+            .piece(sq("a1"), 6, WHITE).piece(sq("d1"), 7, WHITE)
+            .piece(sq("h8"), 6, BLACK).piece(sq("g8"), 4, BLACK)
+            .sideToMove(BLACK)
+            .build();
+        Search s = new Search();
+        s.pushHistory(b);
+        play(s, b, BLACK, "g8h6");
+        play(s, b, WHITE, "d1d2");
+        play(s, b, BLACK, "h6g8");
+        Board.make(b, find(Board.generateMoves(b, WHITE), "d2d1"));
+        assertEquals("black repeats", "g8h6", s.bestMove(b, BLACK, 2).toString());
+        assertFalse("root taken back off", s.isRepetition(2));
+    }
+}
@@ -0,0 +1,115 @@
+package deciduous;
+
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Method;
+import java.lang.reflect.Modifier;
+import java.util.Arrays;
+import java.util.Comparator;
+
+/** The suite of all unit tests for the deciduous package.
+ *  Every static, no argument method named test* in the classes listed in
+ *  main is run in turn; a test fails by throwing, usually through one of
+ *  the assert helpers below. Run it with assertions on, see make check.
+ *  @author Andrew Berger */
+public class UnitTest {
+
+    /** Runs the unit tests, exiting with status 1 if any of them failed. */
+    public static void main(String[] ignored) {
+        Class<?>[] suites = {
+            SearchTest.class, EvalTest.class, BoardTest.class, DefaultsTest.class, MoveTest.class, BoardBuilderTest.class, GameTest.class,
+        };
+        int run = 0;
+        int failed = 0;
+        for (Class<?> suite : suites) {
+            Method[] methods = suite.getDeclaredMethods();
+            Arrays.sort(methods, new Comparator<Method>() {
+                public int compare(Method a, Method b) {
+                    return a.getName().compareTo(b.getName());
+                }
+            });
+            for (Method m : methods) {
+                if (!m.getName().startsWith("test") || m.getParameterCount() != 0
+                    || !Modifier.isStatic(m.getModifiers())) {
+                    continue;
+                }
+                run += 1;
+                try {
+                    m.setAccessible(true);
+                    m.invoke(null);
+                } catch (InvocationTargetException e) {
+                    failed += 1;
+                    System.out.printf("FAIL %s.%s: %s%n", suite.getSimpleName(),
+                                      m.getName(), e.getCause());
+                } catch (IllegalAccessException e) {
+                    failed += 1;
+                    System.out.printf("FAIL %s.%s: %s%n", suite.getSimpleName(), m.getName(), e);
+                }
+            }
+        }
+        System.out.printf("%d of %d tests passed%n", run - failed, run);
+        System.exit(failed == 0 ? 0 : 1);
+    }
+
+    /** Fails with MSG unless COND holds */
+    static void assertTrue(String msg, boolean cond) {
+        if (!cond) {
+            throw new AssertionError(msg);
+        }
+    }
+
+    /** Fails with MSG if COND holds */
+    static void assertFalse(String msg, boolean cond) {
+        assertTrue(msg, !cond);
+    }
+
+    /** Fails with MSG unless EXPECTED equals ACTUAL */
+    static void assertEquals(String msg, long expected, long actual) {
+        if (expected != actual) {
+            throw new AssertionError(msg + ": expected " + expected + ", got " + actual);
+        }
+    }
+
+    /** Fails with MSG unless EXPECTED equals ACTUAL, both possibly null */
+    static void assertEquals(String msg, Object expected, Object actual) {
+        if (expected == null ? actual != null : !expected.equals(actual)) {
+            throw new AssertionError(msg + ": expected " + expected + ", got " + actual);
+        }
+    }
+
+    /** Fails with MSG */
+    static void fail(String msg) {
+        throw new AssertionError(msg);
+    }
+
+    /** True iff the JVM was started with assertions on, as make check does */
+    static boolean assertionsEnabled() {
+        boolean enabled = false;
+        assert enabled = true;
+        return enabled;
+    }
+
+    /** Returns a fresh board holding the initial position */
+    static long[] startPosition() {
+        return new long[] {
+            Defaults.WHITE_PIECES, Defaults.BLACK_PIECES, Defaults.PAWNS,
+            Defaults.BISHOPS, Defaults.KNIGHTS, Defaults.ROOKS,
+            Defaults.KINGS, Defaults.QUEENS, 0
+        };
+    }
+
+    /** Returns the square named NAME, e.g. 28 for e4 */
+    static int sq(String name) {
+        return (name.charAt(0) - 'a') + 8 * (name.charAt(1) - '1');
+    }
+
+    /** Returns the move in MOVES written as NAME in coordinate notation,
+     *  or null if there isn't one. */
+    static Move find(Iterable<Move> moves, String name) {
+        for (Move m : moves) {
+            if (m.toString().equals(name)) {
+                return m;
+            }
+        }
+        return null;
+    }
+}